@@ -0,0 +1,193 @@
+//! Order store throughput: `OrderTable` against `HashMap<u64, CompactOrder>`,
+//! and the same operations as messages through `Book` (whose `Orders` 
+//! are kept in an `OrderTable`).
+//! Run with `cargo bench --bench order_store`.
+//!
+//! The message mix approximates a trading day: reference numbers are 
+//! assigned in increasing order, most orders are deleted shortly after 
+//! being added, and a few rest for much longer.
+
+use std::collections::HashMap;
+use std::time::{ Duration, Instant };
+
+use litch::ItchMessage;
+use litch::book::{ Book, CompactOrder, OrderTable };
+use litch::msg::*;
+use nsdq_util::{ Price, StockSymbol };
+
+const MESSAGES: u64 = 20_000_000;
+
+#[derive(Clone, Copy)]
+enum Op {
+    Add(u64),
+    Execute(u64, u32),
+    Cancel(u64, u32),
+    Delete(u64),
+    Replace(u64, u64),
+}
+
+/// Small deterministic generator, so both stores see the same operations.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn operations() -> Vec<Op> {
+
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut live: Vec<u64> = Vec::new();
+    let mut next_ref = 1;
+    let mut ops = Vec::with_capacity(MESSAGES as usize);
+
+    while (ops.len() as u64) < MESSAGES {
+        let roll = rng.next() % 100;
+        if live.is_empty() || roll < 45 {
+            ops.push(Op::Add(next_ref));
+            live.push(next_ref);
+            next_ref += 1;
+            continue
+        }
+
+        // Mostly recent orders, occasionally an old resting one.
+        let back = match rng.next() % 10 {
+            0 => rng.next() as usize % live.len(),
+            _ => (rng.next() % 64) as usize % live.len(),
+        };
+        let index = live.len() - 1 - back;
+        let order_ref = live[index];
+
+        match roll {
+            45..=84 => { ops.push(Op::Delete(order_ref)); live.swap_remove(index); },
+            85..=92 => ops.push(Op::Execute(order_ref, 10)),
+            93..=95 => ops.push(Op::Cancel(order_ref, 10)),
+            _ => {
+                ops.push(Op::Replace(order_ref, next_ref));
+                live[index] = next_ref;
+                next_ref += 1;
+            },
+        }
+    }
+    ops
+}
+
+fn price() -> Price<u32, 4> {
+    Price::<u32, 4>::parse(&10_0000u32.to_be_bytes()).unwrap().1
+}
+
+fn order(quantity: u32) -> CompactOrder {
+    CompactOrder { price: price(), quantity, stock_locate: 1, side: Side::Buy }
+}
+
+/// The operations as feed messages.
+fn messages(ops: &[Op]) -> Vec<ItchMessage> {
+
+    let metadata = ItchMetadata { stock_locate: 1, tracking_number: 0, nanos: 0 };
+    let stock = StockSymbol::parse(b"TEST    ").unwrap().1;
+
+    ops.iter().map(|op| match *op {
+        Op::Add(order_ref_num) => ItchMessage::OrderAdded { 
+            metadata, 
+            body: OrderAdded { order_ref_num, side: Side::Buy, quantity: 100, stock, price: price() },
+        },
+        Op::Execute(order_ref_num, quantity) => ItchMessage::OrderExecuted { 
+            metadata, 
+            body: OrderExecuted { order_ref_num, quantity, match_number: 0 },
+        },
+        Op::Cancel(order_ref_num, quantity) => ItchMessage::OrderCanceled { 
+            metadata, 
+            body: OrderCanceled { order_ref_num, quantity },
+        },
+        Op::Delete(order_ref_num) => ItchMessage::OrderDeleted { 
+            metadata, 
+            body: OrderDeleted { order_ref_num },
+        },
+        Op::Replace(old_ref_num, new_ref_num) => ItchMessage::OrderReplaced { 
+            metadata, 
+            body: OrderReplaced { old_ref_num, new_ref_num, quantity: 100, price: price() },
+        },
+    }).collect()
+}
+
+trait Store {
+    fn insert(&mut self, order_ref: u64, order: CompactOrder);
+    fn reduce(&mut self, order_ref: u64, quantity: u32) -> Option<CompactOrder>;
+    fn len(&self) -> usize;
+}
+
+impl Store for OrderTable {
+    fn insert(&mut self, order_ref: u64, order: CompactOrder) { 
+        OrderTable::insert(self, order_ref, order); 
+    }
+    fn reduce(&mut self, order_ref: u64, quantity: u32) -> Option<CompactOrder> {
+        OrderTable::reduce(self, order_ref, quantity).map(|(order, _)| order)
+    }
+    fn len(&self) -> usize { OrderTable::len(self) }
+}
+
+impl Store for HashMap<u64, CompactOrder> {
+    fn insert(&mut self, order_ref: u64, order: CompactOrder) { 
+        HashMap::insert(self, order_ref, order); 
+    }
+    fn reduce(&mut self, order_ref: u64, quantity: u32) -> Option<CompactOrder> {
+        let order = self.get_mut(&order_ref)?;
+        order.quantity -= quantity.min(order.quantity);
+        let order = *order;
+        if order.quantity == 0 { self.remove(&order_ref); }
+        Some(order)
+    }
+    fn len(&self) -> usize { HashMap::len(self) }
+}
+
+fn run(store: &mut impl Store, ops: &[Op]) -> Duration {
+
+    let start = Instant::now();
+    for op in ops {
+        match *op {
+            Op::Add(r) => store.insert(r, order(100)),
+            Op::Execute(r, q) | Op::Cancel(r, q) => { store.reduce(r, q); },
+            Op::Delete(r) => { store.reduce(r, u32::MAX); },
+            Op::Replace(old, new) => {
+                if let Some(removed) = store.reduce(old, u32::MAX) {
+                    store.insert(new, CompactOrder { quantity: 100, ..removed });
+                }
+            },
+        }
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration, live: usize) {
+    println!(
+        "{name:>10}: {MESSAGES} messages in {elapsed:.2?} ({:.1} M msg/s), {live} live",
+        MESSAGES as f64 / elapsed.as_secs_f64() / 1e6,
+    );
+}
+
+fn main() {
+
+    let ops = operations();
+
+    let mut table = OrderTable::new();
+    let elapsed = run(&mut table, &ops);
+    report("OrderTable", elapsed, table.len());
+    println!("{table:?}");
+
+    let mut map = HashMap::new();
+    let elapsed = run(&mut map, &ops);
+    report("HashMap", elapsed, Store::len(&map));
+
+    // The full path: order store plus price levels.
+    let messages = messages(&ops);
+    let mut book = Book::new();
+    let start = Instant::now();
+    for msg in &messages {
+        book.update(msg);
+    }
+    report("Book", start.elapsed(), book.orders().len());
+}
@@ -0,0 +1,126 @@
+
+//! Loopback throughput of the single-datagram and batched receive paths.
+//! Run with `cargo bench --bench recvmmsg`.
+//!
+//! Each round fills the socket buffer first and then times only the drain,
+//! so the sender does not limit the measurement.
+
+#[cfg(target_os = "linux")]
+fn main() {
+    linux::run()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}
+
+#[cfg(target_os = "linux")]
+mod linux {
+
+    use std::net::{ SocketAddr, UdpSocket };
+    use std::ops::ControlFlow;
+    use std::time::{ Duration, Instant };
+
+    use litch::ItchMessage;
+    use litch::frame::MoldPacket;
+    use litch::feed::{ BatchReceiver, FeedReceiver };
+
+    const ROUNDS: u64 = 2_000;
+    const PER_ROUND: u64 = 100;
+    const PER_PACKET: u64 = 10;
+
+    fn packet(sequence: u64) -> Vec<u8> {
+
+        let mut bytes = b"BENCH00001".to_vec();
+        bytes.extend(sequence.to_be_bytes());
+        bytes.extend((PER_PACKET as u16).to_be_bytes());
+
+        for i in 0..PER_PACKET {
+            // OrderDeleted: tag, locate, tracking, timestamp, reference number.
+            bytes.extend(19u16.to_be_bytes());
+            bytes.push(b'D');
+            bytes.extend(1u16.to_be_bytes());
+            bytes.extend(0u16.to_be_bytes());
+            bytes.extend(&(sequence + i).to_be_bytes()[2..]);
+            bytes.extend((sequence + i).to_be_bytes());
+        }
+        bytes
+    }
+
+    fn receiver_socket() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket2::SockRef::from(&socket).set_recv_buffer_size(8 << 20).unwrap();
+        socket.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        socket
+    }
+
+    fn fill(sender: &UdpSocket, addr: SocketAddr, round: u64) {
+        for n in 0..PER_ROUND {
+            let seq = 1 + (round * PER_ROUND + n) * PER_PACKET;
+            sender.send_to(&packet(seq), addr).unwrap();
+        }
+    }
+
+    fn report(name: &str, messages: u64, elapsed: Duration) {
+        println!(
+            "{name:>8}: {messages} messages in {elapsed:.2?} ({:.2} M msg/s)",
+            messages as f64 / elapsed.as_secs_f64() / 1e6,
+        );
+    }
+
+    fn single() {
+
+        let socket = receiver_socket();
+        let addr = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut receiver = FeedReceiver::new(socket);
+
+        let mut messages = 0u64;
+        let mut elapsed = Duration::ZERO;
+        for round in 0..ROUNDS {
+            fill(&sender, addr, round);
+            let start = Instant::now();
+            for _ in 0..PER_ROUND {
+                let datagram = receiver.recv().unwrap();
+                let packet = MoldPacket::parse(datagram.bytes).unwrap();
+                for bytes in packet.messages() {
+                    if ItchMessage::from_bytes(bytes).is_ok() { messages += 1 }
+                }
+            }
+            elapsed += start.elapsed();
+        }
+
+        report("recvmsg", messages, elapsed);
+    }
+
+    fn batched() {
+
+        let socket = receiver_socket();
+        let addr = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut receiver = BatchReceiver::with_defaults(socket).unwrap();
+
+        let mut messages = 0u64;
+        let mut elapsed = Duration::ZERO;
+        for round in 0..ROUNDS {
+            fill(&sender, addr, round);
+            let start = Instant::now();
+            let mut datagrams = 0;
+            while datagrams < PER_ROUND as usize {
+                datagrams += receiver.recv_batch().unwrap();
+                let _ = receiver.parse_batch(&mut |msg| {
+                    if msg.is_ok() { messages += 1 }
+                    ControlFlow::Continue(())
+                });
+            }
+            elapsed += start.elapsed();
+        }
+
+        report("recvmmsg", messages, elapsed);
+        println!("{:?}", receiver.stats());
+    }
+
+    pub fn run() {
+        single();
+        batched();
+    }
+}
@@ -0,0 +1,122 @@
+
+//! Compressed ITCH archive with random access by security and time.
+//!
+//! Messages are grouped into one stream per `stock_locate` 
+//! (market-wide messages use locate 0), and each stream is cut into 
+//! zstd-compressed blocks. An index at the end of the file records every 
+//! block's locate and time span, so a query only decompresses the blocks 
+//! of one security and the market-wide stream.
+//!
+//! All integers are little-endian.
+//!
+//! | Section | Layout |
+//! |---|---|
+//! | Header (16 bytes) | magic `LITCHARC`, version `u32`, reserved `u32` |
+//! | Blocks | zstd frames of records: sequence `u64`, length `u16`, message bytes |
+//! | Index | block count `u32`, `BlockInfo` entries (38 bytes each), status count `u32`, status records |
+//! | Footer (24 bytes) | index offset `u64`, index length `u64`, magic `LITCHARC` |
+//!
+//! The sequence number is the message's position in the original file and 
+//! is used to merge streams back into feed order. 
+//! Security status messages (`StockDirectory`, `TradingAction`, 
+//! `RegShoRestriction`, `QuotingPeriodUpdate` and `OperationalHalt`) are also 
+//! kept in the index, so that a query can start with the state of its security.
+
+mod write;
+mod read;
+
+pub use write::ArchiveWriter;
+pub use read::{ ArchiveReader, Query };
+
+use std::io;
+
+const MAGIC: &[u8; 8] = b"LITCHARC";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+const FOOTER_LEN: usize = 24;
+const BLOCK_INFO_LEN: usize = 38;
+/// Tags of the status messages kept in the index.
+const STATUS_TAGS: &[u8] = b"RHYKh";
+
+/// Uncompressed bytes buffered per stream before a block is written.
+pub const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+/// Largest block size a writer accepts.
+pub const MAX_BLOCK_SIZE: usize = 64 * 1024 * 1024;
+/// A block is cut after the record that reaches the block size,
+/// so it can hold one record (up to 65545 bytes) more than that.
+/// Readers refuse to decompress anything larger.
+const MAX_RAW_LEN: usize = MAX_BLOCK_SIZE + 10 + u16::MAX as usize;
+
+/// Index entry for one compressed block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInfo {
+    /// Stream the block belongs to (0 for market-wide messages).
+    pub stock_locate: u16,
+    /// Number of messages in the block.
+    pub messages: u32,
+    /// Earliest timestamp in the block (nanoseconds since midnight).
+    pub first_nanos: u64,
+    /// Latest timestamp in the block (nanoseconds since midnight).
+    pub last_nanos: u64,
+    /// Byte offset of the compressed block in the file.
+    pub offset: u64,
+    /// Compressed length in bytes.
+    pub len: u32,
+    /// Uncompressed length in bytes.
+    pub raw_len: u32,
+}
+
+impl BlockInfo {
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.stock_locate.to_le_bytes());
+        out.extend_from_slice(&self.messages.to_le_bytes());
+        out.extend_from_slice(&self.first_nanos.to_le_bytes());
+        out.extend_from_slice(&self.last_nanos.to_le_bytes());
+        out.extend_from_slice(&self.offset.to_le_bytes());
+        out.extend_from_slice(&self.len.to_le_bytes());
+        out.extend_from_slice(&self.raw_len.to_le_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        Ok(Self {
+            stock_locate: take_u16(input)?,
+            messages: take_u32(input)?,
+            first_nanos: take_u64(input)?,
+            last_nanos: take_u64(input)?,
+            offset: take_u64(input)?,
+            len: take_u32(input)?,
+            raw_len: take_u32(input)?,
+        })
+    }
+
+    /// True if the block may hold messages in `start..end` (nanoseconds).
+    fn overlaps(&self, start: u64, end: u64) -> bool {
+        self.first_nanos < end && self.last_nanos >= start
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+    if input.len() < n {
+        return Err(invalid("Archive is truncated"))
+    }
+    let (head, rest) = input.split_at(n);
+    *input = rest;
+    Ok(head)
+}
+
+fn take_u16(input: &mut &[u8]) -> io::Result<u16> {
+    Ok(u16::from_le_bytes(take(input, 2)?.try_into().unwrap()))
+}
+
+fn take_u32(input: &mut &[u8]) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(take(input, 4)?.try_into().unwrap()))
+}
+
+fn take_u64(input: &mut &[u8]) -> io::Result<u64> {
+    Ok(u64::from_le_bytes(take(input, 8)?.try_into().unwrap()))
+}
@@ -0,0 +1,234 @@
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+
+use memmap2::Mmap;
+use nsdq_util::NaiveTime;
+
+use crate::{ Error, ItchMessage };
+use crate::msg::nanos_of_day;
+use super::*;
+
+/// Random-access reader for an archive written by `ArchiveWriter`.
+pub struct ArchiveReader {
+    map: Mmap,
+    blocks: Vec<BlockInfo>,
+    directory: HashMap<u16, (u64, ItchMessage)>,
+    /// Other status messages of each security, in feed order.
+    status: HashMap<u16, Vec<(u64, ItchMessage)>>,
+    symbols: HashMap<[u8; 8], u16>,
+}
+
+impl ArchiveReader {
+
+    /// Open an archive and load its index.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+
+        let file = File::open(path)?;
+        // SAFETY: Archives are written once and not modified while read.
+        let map = unsafe { Mmap::map(&file)? };
+
+        if map.len() < HEADER_LEN + FOOTER_LEN || &map[..8] != MAGIC {
+            return Err(invalid("Not a litch archive").into())
+        }
+        if u32::from_le_bytes(map[8..12].try_into().unwrap()) != VERSION {
+            return Err(invalid("Unsupported archive version").into())
+        }
+
+        let mut footer = &map[map.len() - FOOTER_LEN..];
+        let index_offset = take_u64(&mut footer)? as usize;
+        let index_len = take_u64(&mut footer)? as usize;
+        if footer != MAGIC {
+            return Err(invalid("Archive was not finished").into())
+        }
+
+        let mut index = map.get(index_offset..index_offset + index_len)
+            .ok_or_else(|| invalid("Archive index is out of bounds"))?;
+
+        let count = take_u32(&mut index)? as usize;
+        if index.len() < count * BLOCK_INFO_LEN {
+            return Err(invalid("Archive is truncated").into())
+        }
+        let blocks = (0..count)
+            .map(|_| BlockInfo::decode(&mut index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut directory = HashMap::new();
+        let mut status = HashMap::<u16, Vec<_>>::new();
+        let mut symbols = HashMap::new();
+        for _ in 0..take_u32(&mut index)? {
+            let sequence = take_u64(&mut index)?;
+            let len = take_u16(&mut index)? as usize;
+            let msg = ItchMessage::from_bytes(take(&mut index, len)?)?;
+            let locate = msg.metadata().stock_locate;
+            if let ItchMessage::StockDirectory { body, .. } = msg {
+                symbols.insert(body.stock.encode(), locate);
+                directory.insert(locate, (sequence, msg));
+            } else {
+                status.entry(locate).or_default().push((sequence, msg));
+            }
+        }
+
+        Ok(Self { map, blocks, directory, status, symbols })
+    }
+
+    /// Index entries for every block, in file order.
+    pub fn blocks(&self) -> &[BlockInfo] { &self.blocks }
+
+    /// Locate code assigned to a symbol by its `StockDirectory` message.
+    pub fn locate(&self, symbol: &str) -> Option<u16> {
+        let symbol = nsdq_util::StockSymbol::from(symbol).ok()?;
+        self.symbols.get(&symbol.encode()).copied()
+    }
+
+    /// `StockDirectory` message for a locate, if the archive has one.
+    pub fn directory(&self, locate: u16) -> Option<ItchMessage> {
+        self.directory.get(&locate).map(|(_, msg)| *msg)
+    }
+
+    /// Messages for one locate generated within `range`, in feed order.
+    ///
+    /// Market-wide messages (locate 0) generated before the end of `range` 
+    /// are always included, as are the security's `StockDirectory` message 
+    /// and its latest `TradingAction`, `RegShoRestriction`, 
+    /// `QuotingPeriodUpdate` and `OperationalHalt` from before `start`,
+    /// so that the caller sees the system and security state as of `start`.
+    pub fn query(&self, locate: u16, range: Range<NaiveTime>) -> Query<'_> {
+
+        let start = nanos_of_day(range.start);
+        let end = nanos_of_day(range.end);
+
+        let blocks = |locate: u16, start: u64| -> Vec<&BlockInfo> {
+            self.blocks.iter()
+                .filter(|b| b.stock_locate == locate && b.overlaps(start, end))
+                .collect()
+        };
+
+        let market = Cursor::new(&self.map, blocks(0, 0), 0..end);
+        let security = match locate {
+            0 => Cursor::new(&self.map, Vec::new(), start..end),
+            _ => Cursor::new(&self.map, blocks(locate, start), start..end),
+        };
+
+        // Status messages inside the range arrive through the stream.
+        let before_start = |(_, msg): &&(u64, ItchMessage)| msg.metadata().nanos < start;
+        let mut context: Vec<(u64, ItchMessage)> = Vec::new();
+        let status = self.status.get(&locate).into_iter().flatten();
+        for record in status.filter(before_start) {
+            // Keep only the latest message of each kind.
+            context.retain(|(_, msg)| msg.kind() != record.1.kind());
+            context.push(*record);
+        }
+        context.extend(self.directory.get(&locate).filter(before_start));
+        context.sort_by_key(|(sequence, _)| *sequence);
+
+        Query { 
+            context: context.into_iter(), 
+            heads: [None, None, None], 
+            cursors: [market, security],
+        }
+    }
+
+    /// Messages for a symbol within `range`, as `query`.
+    /// Returns `None` if the archive has no directory entry for the symbol.
+    pub fn query_symbol(&self, symbol: &str, range: Range<NaiveTime>) -> Option<Query<'_>> {
+        Some(self.query(self.locate(symbol)?, range))
+    }
+}
+
+/// Reads the messages of one stream, block by block.
+struct Cursor<'a> {
+    map: &'a [u8],
+    blocks: std::vec::IntoIter<&'a BlockInfo>,
+    window: Range<u64>,
+    block: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+
+    fn new(map: &'a [u8], blocks: Vec<&'a BlockInfo>, window: Range<u64>) -> Self {
+        Self { map, blocks: blocks.into_iter(), window, block: Vec::new(), pos: 0 }
+    }
+
+    fn next(&mut self) -> Result<Option<(u64, ItchMessage)>, Error> {
+
+        loop {
+            if self.pos >= self.block.len() {
+                let Some(info) = self.blocks.next() else { return Ok(None) };
+                let start = info.offset as usize;
+                let data = self.map.get(start..start + info.len as usize)
+                    .ok_or_else(|| invalid("Archive block is out of bounds"))?;
+                // The length comes from the file: don't let it size the buffer.
+                if info.raw_len as usize > MAX_RAW_LEN {
+                    return Err(invalid("Archive block is too large").into())
+                }
+                self.block = zstd::bulk::decompress(data, info.raw_len as usize)?;
+                self.pos = 0;
+            }
+
+            let (sequence, msg) = match self.record() {
+                Ok(record) => record,
+                Err(e) => {
+                    // Stop reading the stream rather than repeat the error.
+                    self.pos = self.block.len();
+                    self.blocks = Vec::new().into_iter();
+                    return Err(e)
+                },
+            };
+
+            if self.window.contains(&msg.metadata().nanos) {
+                return Ok(Some((sequence, msg)))
+            }
+        }
+    }
+
+    fn record(&mut self) -> Result<(u64, ItchMessage), Error> {
+
+        let mut rest = &self.block[self.pos..];
+        let sequence = take_u64(&mut rest)?;
+        let len = take_u16(&mut rest)? as usize;
+        let msg = ItchMessage::from_bytes(take(&mut rest, len)?)?;
+        self.pos = self.block.len() - rest.len();
+
+        Ok((sequence, msg))
+    }
+}
+
+/// Iterator over the result of an archive query, 
+/// merging the market-wide and security streams in feed order.
+pub struct Query<'a> {
+    /// Status messages from before the range, in feed order.
+    context: std::vec::IntoIter<(u64, ItchMessage)>,
+    /// Next message from the context, market and security sources.
+    heads: [Option<(u64, ItchMessage)>; 3],
+    cursors: [Cursor<'a>; 2],
+}
+
+impl Iterator for Query<'_> {
+
+    type Item = Result<ItchMessage, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        if self.heads[0].is_none() {
+            self.heads[0] = self.context.next();
+        }
+        for (head, cursor) in self.heads[1..].iter_mut().zip(&mut self.cursors) {
+            if head.is_none() {
+                match cursor.next() {
+                    Ok(next) => *head = next,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+        }
+
+        let earliest = self.heads.iter_mut()
+            .filter(|head| head.is_some())
+            .min_by_key(|head| head.map(|(sequence, _)| sequence))?;
+
+        earliest.take().map(|(_, msg)| Ok(msg))
+    }
+}
@@ -0,0 +1,189 @@
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{ BufWriter, Write };
+use std::path::Path;
+
+use crate::Error;
+use crate::file::ItchFile;
+use crate::msg::ItchMetadata;
+use super::{ 
+    BlockInfo, 
+    DEFAULT_BLOCK_SIZE, 
+    FOOTER_LEN, 
+    HEADER_LEN, 
+    MAGIC, 
+    MAX_BLOCK_SIZE, 
+    STATUS_TAGS, 
+    VERSION,
+};
+
+/// Messages buffered for one locate, waiting to be compressed.
+#[derive(Default)]
+struct Stream {
+    buf: Vec<u8>,
+    messages: u32,
+    first_nanos: u64,
+    last_nanos: u64,
+}
+
+/// Writes raw ITCH messages into an indexed archive.
+/// Call `finish` to write the index; an unfinished archive cannot be read.
+pub struct ArchiveWriter {
+    out: BufWriter<File>,
+    offset: u64,
+    block_size: usize,
+    level: i32,
+    streams: HashMap<u16, Stream>,
+    blocks: Vec<BlockInfo>,
+    status: Vec<(u64, Vec<u8>)>,
+    sequence: u64,
+}
+
+impl ArchiveWriter {
+
+    /// Create (or truncate) an archive at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, Error> {
+
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&[0; 4])?;
+
+        Ok(Self {
+            out,
+            offset: HEADER_LEN as u64,
+            block_size: DEFAULT_BLOCK_SIZE,
+            level: zstd::DEFAULT_COMPRESSION_LEVEL,
+            streams: HashMap::new(),
+            blocks: Vec::new(),
+            status: Vec::new(),
+            sequence: 0,
+        })
+    }
+
+    /// Uncompressed bytes buffered per locate before a block is written
+    /// (at most `MAX_BLOCK_SIZE`).
+    /// Smaller blocks make queries finer-grained at some cost in ratio.
+    pub fn block_size(mut self, bytes: usize) -> Self {
+        self.block_size = bytes.clamp(1, MAX_BLOCK_SIZE);
+        self
+    }
+
+    /// zstd compression level.
+    pub fn level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Convert a raw length-prefixed ITCH file (e.g. a decompressed 
+    /// `NASDAQ_ITCH50` day) into an archive. Returns the number of messages.
+    pub fn convert(
+        input: impl AsRef<Path>, 
+        output: impl AsRef<Path>
+    ) -> Result<u64, Error> {
+
+        let file = ItchFile::open(input)?;
+        let mut messages = file.messages();
+
+        let mut writer = Self::create(output)?;
+        while let Some(msg) = messages.next_raw() {
+            writer.push(msg)?;
+        }
+        writer.finish()
+    }
+
+    /// Number of messages written so far.
+    pub fn messages(&self) -> u64 { self.sequence }
+
+    /// Append one raw message (without its length prefix).
+    pub fn push(&mut self, msg: &[u8]) -> Result<(), Error> {
+
+        let len = u16::try_from(msg.len())
+            .map_err(|_| Error::Frame("Message is longer than 65535 bytes"))?;
+        let (_, metadata) = msg.get(1..)
+            .and_then(|body| ItchMetadata::parse(body).ok())
+            .ok_or_else(|| Error::message(msg))?;
+
+        if STATUS_TAGS.contains(&msg[0]) {
+            self.status.push((self.sequence, msg.to_vec()));
+        }
+
+        let stream = self.streams.entry(metadata.stock_locate).or_default();
+        if stream.messages == 0 {
+            stream.first_nanos = metadata.nanos;
+            stream.last_nanos = metadata.nanos;
+        }
+        stream.first_nanos = stream.first_nanos.min(metadata.nanos);
+        stream.last_nanos = stream.last_nanos.max(metadata.nanos);
+        stream.messages += 1;
+        stream.buf.extend_from_slice(&self.sequence.to_le_bytes());
+        stream.buf.extend_from_slice(&len.to_le_bytes());
+        stream.buf.extend_from_slice(msg);
+        self.sequence += 1;
+
+        if stream.buf.len() >= self.block_size {
+            self.flush_stream(metadata.stock_locate)?;
+        }
+
+        Ok(())
+    }
+
+    fn flush_stream(&mut self, locate: u16) -> Result<(), Error> {
+
+        let Some(stream) = self.streams.get_mut(&locate) else { return Ok(()) };
+        if stream.messages == 0 { return Ok(()) }
+
+        let compressed = zstd::bulk::compress(&stream.buf, self.level)?;
+        self.out.write_all(&compressed)?;
+
+        self.blocks.push(BlockInfo {
+            stock_locate: locate,
+            messages: stream.messages,
+            first_nanos: stream.first_nanos,
+            last_nanos: stream.last_nanos,
+            offset: self.offset,
+            len: compressed.len() as u32,
+            raw_len: stream.buf.len() as u32,
+        });
+        self.offset += compressed.len() as u64;
+
+        stream.buf.clear();
+        stream.messages = 0;
+        Ok(())
+    }
+
+    /// Write the remaining blocks and the index.
+    /// Returns the number of messages in the archive.
+    pub fn finish(mut self) -> Result<u64, Error> {
+
+        let mut locates: Vec<u16> = self.streams.keys().copied().collect();
+        locates.sort_unstable();
+        for locate in locates {
+            self.flush_stream(locate)?;
+        }
+
+        let mut index = Vec::new();
+        index.extend_from_slice(&(self.blocks.len() as u32).to_le_bytes());
+        for block in &self.blocks {
+            block.encode(&mut index);
+        }
+        index.extend_from_slice(&(self.status.len() as u32).to_le_bytes());
+        for (sequence, msg) in &self.status {
+            index.extend_from_slice(&sequence.to_le_bytes());
+            index.extend_from_slice(&(msg.len() as u16).to_le_bytes());
+            index.extend_from_slice(msg);
+        }
+
+        let mut footer = Vec::with_capacity(FOOTER_LEN);
+        footer.extend_from_slice(&self.offset.to_le_bytes());
+        footer.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        footer.extend_from_slice(MAGIC);
+
+        self.out.write_all(&index)?;
+        self.out.write_all(&footer)?;
+        self.out.flush()?;
+
+        Ok(self.sequence)
+    }
+}
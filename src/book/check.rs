@@ -0,0 +1,190 @@
+
+use std::collections::{ HashMap, HashSet };
+use nsdq_util::Price;
+
+use crate::msg::{ ItchMessage, ItchMetadata, CrossType, TradingState };
+use crate::clock::{ MARKET_CLOSE, MARKET_OPEN };
+use crate::session::Phase;
+use super::{ Book, BookView, OrderUpdate };
+
+/// Something in the feed that is inconsistent with the book built so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anomaly {
+
+    /// Metadata of the offending message.
+    pub metadata: ItchMetadata,
+    /// What was inconsistent.
+    pub kind: AnomalyKind,
+}
+
+/// Kinds of book inconsistencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+
+    /// An execution, cancel, delete or replace refers to an order 
+    /// that is not on the book (e.g. after a gap or a late start).
+    UnknownOrder { order_ref_num: u64 },
+
+    /// An execution or cancel removes more shares than the order has left.
+    Overfill { order_ref_num: u64, remaining: u32, requested: u32 },
+
+    /// An add reuses the reference number of an order still on the book.
+    DuplicateAdd { order_ref_num: u64 },
+
+    /// A replace assigns a reference number that is still on the book.
+    ReplaceReusesLive { new_ref_num: u64 },
+
+    /// Best bid equals best offer while the security is trading.
+    Locked { price: Price<u32, 4> },
+
+    /// Best bid is above best offer while the security is trading.
+    Crossed { bid: Price<u32, 4>, ask: Price<u32, 4> },
+}
+
+/// Validates every message against the book it was applied to.
+///
+/// Locked and crossed books are expected outside continuous trading:
+/// outside market hours, before a security's opening cross 
+/// (or first execution), and while a security is halted, paused 
+/// or in a quotation-only period. They are only reported in continuous 
+/// trading, when the book becomes locked or crossed, or when a security 
+/// resumes trading with a locked or crossed book.
+#[derive(Debug, Clone, Default)]
+pub struct BookChecker {
+    phase: Phase,
+    /// Securities whose opening cross period is over.
+    opened: HashSet<u16>,
+    states: HashMap<u16, TradingState>,
+    crossed: HashMap<u16, bool>,
+}
+
+impl BookView for BookChecker {
+
+    type Output = Vec<Anomaly>;
+
+    /// Returns any anomalies the message revealed.
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) -> Vec<Anomaly> {
+
+        let metadata = msg.metadata();
+        let mut anomalies: Vec<_> = check_orders(update, msg)
+            .map(|kind| Anomaly { metadata, kind })
+            .into_iter()
+            .collect();
+
+        self.track_session(msg);
+
+        // A trading action or cross may start continuous trading.
+        let locate = match msg {
+            ItchMessage::TradingAction { .. } | ItchMessage::CrossTrade { .. } => 
+                Some(metadata.stock_locate),
+            _ => update.iter().next().map(|c| c.order.stock_locate),
+        };
+        if let Some(kind) = locate.and_then(|l| self.check_top(book, l, metadata.nanos)) {
+            anomalies.push(Anomaly { metadata, kind })
+        }
+
+        anomalies
+    }
+}
+
+impl BookChecker {
+
+    /// Create a checker.
+    pub fn new() -> Self { Self::default() }
+
+    // Follow the market phase, and each security's trading state and open.
+    fn track_session(&mut self, msg: &ItchMessage) {
+
+        let locate = msg.metadata().stock_locate;
+        match msg {
+            ItchMessage::SystemEvent { body, .. } => {
+                self.phase = Phase::after(*body);
+                if self.phase == Phase::StartOfMessages {
+                    self.opened.clear();
+                }
+            },
+            ItchMessage::TradingAction { body, .. } => {
+                self.states.insert(locate, body.state);
+            },
+            ItchMessage::CrossTrade { body, .. } if body.cross_type == CrossType::Opening => {
+                self.opened.insert(locate);
+            },
+            ItchMessage::OrderExecuted { .. } 
+            | ItchMessage::OrderExecutedWithPrice { .. } 
+            | ItchMessage::MatchTrade { .. } if self.phase == Phase::MarketHours => {
+                self.opened.insert(locate);
+            },
+            _ => {},
+        }
+    }
+
+    // True if a security is in continuous trading, 
+    // where its book should be neither locked nor crossed.
+    fn continuous(&self, locate: u16, nanos: u64) -> bool {
+
+        let market_hours = match self.phase {
+            Phase::NotStarted => (MARKET_OPEN..MARKET_CLOSE).contains(&nanos),
+            phase => phase == Phase::MarketHours,
+        };
+        let trading = self.states.get(&locate).is_none_or(|s| *s == TradingState::Trading);
+
+        market_hours 
+            && trading 
+            && (self.phase == Phase::NotStarted || self.opened.contains(&locate))
+    }
+
+    // Report a security's book when it becomes locked or crossed while trading.
+    // The state is only recorded in continuous trading, so a book that is 
+    // still crossed when continuous trading starts is reported then.
+    fn check_top(&mut self, book: &Book, locate: u16, nanos: u64) -> Option<AnomalyKind> {
+
+        let stock = book.stock(locate)?;
+        let top = stock.best_bid().zip(stock.best_ask())
+            .map(|(bid, ask)| (bid.price, ask.price))
+            .filter(|(bid, ask)| bid.val() >= ask.val())
+            .filter(|_| self.continuous(locate, nanos));
+
+        let was_crossed = self.crossed.insert(locate, top.is_some()).unwrap_or(false);
+        if was_crossed { return None }
+
+        top.map(|(bid, ask)| match bid.val() == ask.val() {
+            true => AnomalyKind::Locked { price: bid },
+            false => AnomalyKind::Crossed { bid, ask },
+        })
+    }
+}
+
+// Check an order message against the changes it caused.
+fn check_orders(update: &OrderUpdate, msg: &ItchMessage) -> Option<AnomalyKind> {
+
+    // Shares actually removed, if the order was on the book.
+    let removed = || update.iter().next().map(|c| (-c.delta) as u32);
+    let reduce = |order_ref_num: u64, requested: u32| match removed() {
+        None => Some(AnomalyKind::UnknownOrder { order_ref_num }),
+        Some(remaining) if remaining < requested => Some(AnomalyKind::Overfill { 
+            order_ref_num, 
+            remaining, 
+            requested,
+        }),
+        Some(_) => None,
+    };
+    // A live order with the same reference number is removed first.
+    let changes = update.iter().count();
+    let add = |order_ref_num: u64| (changes > 1)
+        .then_some(AnomalyKind::DuplicateAdd { order_ref_num });
+
+    match msg {
+        ItchMessage::OrderAdded { body, .. } => add(body.order_ref_num),
+        ItchMessage::OrderAddedWithMpid { body, .. } => add(body.order_ref_num),
+        ItchMessage::OrderExecuted { body, .. } => reduce(body.order_ref_num, body.quantity),
+        ItchMessage::OrderExecutedWithPrice { body, .. } => reduce(body.order_ref_num, body.quantity),
+        ItchMessage::OrderCanceled { body, .. } => reduce(body.order_ref_num, body.quantity),
+        ItchMessage::OrderDeleted { body, .. } => reduce(body.order_ref_num, 0),
+        ItchMessage::OrderReplaced { body, .. } => match changes {
+            0 => Some(AnomalyKind::UnknownOrder { order_ref_num: body.old_ref_num }),
+            3 => Some(AnomalyKind::ReplaceReusesLive { new_ref_num: body.new_ref_num }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
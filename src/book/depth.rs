@@ -0,0 +1,231 @@
+
+use std::collections::{ BTreeMap, HashMap };
+use nsdq_util::Price;
+
+use crate::msg::{ ItchMessage, ItchMetadata, Side };
+use super::{ OrderChange, OrderUpdate, Orders };
+
+/// Aggregate of all displayed orders at a single price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceLevel {
+
+    /// Price of the level.
+    pub price: Price<u32, 4>,
+    /// Total displayed shares.
+    pub size: u64,
+    /// Number of orders resting at this price.
+    pub orders: u32,
+}
+
+/// New state of a price level after a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelDelta {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Side of the book.
+    pub side: Side,
+    /// Price of the level.
+    pub price: Price<u32, 4>,
+    /// Total displayed shares now at the level.
+    pub size: u64,
+    /// Number of orders now at the level (zero if the level was removed).
+    pub orders: u32,
+}
+
+impl LevelDelta {
+
+    /// True if the level no longer has any orders.
+    pub fn is_removal(&self) -> bool { self.orders == 0 }
+}
+
+/// Price level changes caused by a single message.
+/// An `OrderReplaced` produces the delta for the old level, then the new one;
+/// if both are the same level only its final state is reported.
+/// (A stale order removed by an add onto a live reference number
+/// may add a third.)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelUpdate([Option<LevelDelta>; 3]);
+
+impl LevelUpdate {
+
+    fn push(&mut self, delta: LevelDelta) {
+        let same_level = |d: &LevelDelta| 
+            d.side == delta.side && d.price == delta.price && d.stock_locate == delta.stock_locate;
+        if let Some(slot) = self.0.iter_mut()
+            .find(|d| d.is_none() || d.as_ref().is_some_and(same_level)) 
+        {
+            *slot = Some(delta)
+        }
+    }
+
+    /// Iterate over the deltas in the order they were applied.
+    pub fn iter(&self) -> impl Iterator<Item = &LevelDelta> {
+        self.0.iter().flatten()
+    }
+
+    /// True if the message did not affect any price level.
+    pub fn is_empty(&self) -> bool { self.0[0].is_none() }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Aggregate {
+    size: u64,
+    orders: u32,
+}
+
+/// Price levels for both sides of a single security.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StockBook {
+    bids: BTreeMap<u32, Aggregate>,
+    asks: BTreeMap<u32, Aggregate>,
+}
+
+impl StockBook {
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<u32, Aggregate> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    fn level((price, agg): (&u32, &Aggregate)) -> PriceLevel {
+        PriceLevel { price: super::price(*price), size: agg.size, orders: agg.orders }
+    }
+
+    // Apply an order change, returning the new state of the affected level.
+    pub(crate) fn apply(&mut self, change: &OrderChange) -> Option<PriceLevel> {
+
+        let order = &change.order;
+        let price = order.price.val();
+        let levels = self.side_mut(order.side);
+
+        // Zero-share orders were never counted at the level.
+        if change.delta == 0 {
+            return levels.get_key_value(&price).map(Self::level)
+        }
+
+        let agg = levels.entry(price).or_default();
+
+        agg.size = agg.size.saturating_add_signed(change.delta);
+        if change.delta > 0 && order.quantity as i64 == change.delta {
+            agg.orders += 1;
+        } else if change.is_removal() {
+            agg.orders = agg.orders.saturating_sub(1);
+        }
+
+        if agg.orders == 0 {
+            levels.remove(&price);
+            None
+        } else {
+            Some(PriceLevel { price: order.price, size: agg.size, orders: agg.orders })
+        }
+    }
+
+    /// Highest bid.
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids.iter().next_back().map(Self::level)
+    }
+
+    /// Lowest offer.
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks.iter().next().map(Self::level)
+    }
+
+    /// Bid levels, best (highest) first.
+    pub fn bids(&self) -> impl Iterator<Item = PriceLevel> + '_ {
+        self.bids.iter().rev().map(Self::level)
+    }
+
+    /// Ask levels, best (lowest) first.
+    pub fn asks(&self) -> impl Iterator<Item = PriceLevel> + '_ {
+        self.asks.iter().map(Self::level)
+    }
+
+    /// Level at an exact price on one side.
+    pub fn level_at(&self, side: Side, price: Price<u32, 4>) -> Option<PriceLevel> {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        levels.get_key_value(&price.val()).map(Self::level)
+    }
+
+    /// True if both sides are empty.
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+/// Market-by-price book for every security, keyed by `stock_locate`.
+///
+/// A single book can feed any number of views (see `BookView`).
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    orders: Orders,
+    stocks: HashMap<u16, StockBook>,
+    last: Option<ItchMetadata>,
+}
+
+impl Book {
+
+    /// Create an empty book.
+    pub fn new() -> Self { Self::default() }
+
+    /// Apply a message, returning the order changes it caused.
+    pub fn update(&mut self, msg: &ItchMessage) -> OrderUpdate {
+
+        let update = self.orders.apply(msg);
+        for change in update.iter() {
+            self.stocks.entry(change.order.stock_locate)
+                .or_default()
+                .apply(change);
+        }
+
+        self.last = Some(msg.metadata());
+        update
+    }
+
+    /// Apply a message, returning the price levels it changed.
+    pub fn update_levels(&mut self, msg: &ItchMessage) -> LevelUpdate {
+        let update = self.update(msg);
+        self.levels(&update)
+    }
+
+    /// Current state of the price levels touched by an update 
+    /// that has been applied to the book.
+    pub fn levels(&self, update: &OrderUpdate) -> LevelUpdate {
+
+        let mut levels = LevelUpdate::default();
+        for change in update.iter() {
+            let order = &change.order;
+            let level = self.stock(order.stock_locate)
+                .and_then(|stock| stock.level_at(order.side, order.price));
+            levels.push(LevelDelta {
+                stock_locate: order.stock_locate,
+                side: order.side,
+                price: order.price,
+                size: level.map_or(0, |l| l.size),
+                orders: level.map_or(0, |l| l.orders),
+            });
+        }
+        levels
+    }
+
+    /// Book for a single security.
+    pub fn stock(&self, stock_locate: u16) -> Option<&StockBook> {
+        self.stocks.get(&stock_locate)
+    }
+
+    /// Iterate over the books of all securities with orders.
+    pub fn stocks(&self) -> impl Iterator<Item = (u16, &StockBook)> {
+        self.stocks.iter().map(|(locate, book)| (*locate, book))
+    }
+
+    /// Access the underlying order store.
+    pub fn orders(&self) -> &Orders { &self.orders }
+
+    /// Metadata of the last message applied to the book.
+    pub fn last_applied(&self) -> Option<ItchMetadata> { self.last }
+}
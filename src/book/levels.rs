@@ -0,0 +1,56 @@
+
+use nsdq_util::{ NaiveTime, StockSymbol };
+
+use crate::msg::ItchMessage;
+use super::{ Book, BookView, LevelUpdate, OrderUpdate, Symbols };
+
+/// Price level changes in one security caused by a single message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthUpdate {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Symbol of the security.
+    pub stock: StockSymbol,
+    /// New state of each affected level, in the order applied.
+    pub levels: LevelUpdate,
+    /// Time of the message that changed the levels.
+    pub timestamp: NaiveTime,
+}
+
+/// Level 2 (market-by-price) view of the book: emits the new aggregate size 
+/// and order count of every level a message touches, so that consumers can 
+/// maintain depth without tracking individual orders.
+#[derive(Debug, Clone, Default)]
+pub struct LevelTracker {
+    symbols: Symbols,
+}
+
+impl LevelTracker {
+
+    /// Create a tracker.
+    pub fn new() -> Self { Self::default() }
+}
+
+impl BookView for LevelTracker {
+
+    type Output = Option<DepthUpdate>;
+
+    /// Returns the levels the message changed (if any).
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) -> Option<DepthUpdate> {
+
+        self.symbols.update(msg);
+
+        let levels = book.levels(update);
+        // Every level change of a message belongs to a single security.
+        let locate = levels.iter().next()?.stock_locate;
+
+        Some(DepthUpdate {
+            stock_locate: locate,
+            // Orders are only known from adds, which carry the symbol.
+            stock: self.symbols.get(locate)?,
+            levels,
+            timestamp: msg.metadata().timestamp(),
+        })
+    }
+}
@@ -0,0 +1,227 @@
+
+use std::collections::HashMap;
+
+use crate::msg::ItchMessage;
+use super::{ Book, BookView, OrderChange, OrderUpdate, StockBook };
+
+/// Round lot size assumed until the stock directory says otherwise.
+pub const DEFAULT_ROUND_LOT: u32 = 100;
+
+/// Size classification of an order or execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LotClass {
+    /// Fewer shares than a round lot.
+    Odd,
+    /// An exact multiple of the round lot size.
+    Round,
+    /// More than a round lot, but not a multiple of it.
+    Mixed,
+}
+
+impl LotClass {
+
+    /// Classify a quantity of shares against a round lot size.
+    pub fn of(quantity: u32, round_lot: u32) -> Self {
+        let round_lot = round_lot.max(1);
+        if quantity < round_lot {
+            LotClass::Odd
+        } else if quantity.is_multiple_of(round_lot) {
+            LotClass::Round
+        } else {
+            LotClass::Mixed
+        }
+    }
+}
+
+/// Number of orders or executions, and the shares they represent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LotVolume {
+
+    /// Number of orders or executions.
+    pub count: u64,
+    /// Total shares.
+    pub shares: u64,
+}
+
+/// Volume split by lot classification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LotStats {
+
+    /// Odd lot volume.
+    pub odd: LotVolume,
+    /// Round lot volume.
+    pub round: LotVolume,
+    /// Mixed lot volume.
+    pub mixed: LotVolume,
+}
+
+impl LotStats {
+
+    /// Volume for one classification.
+    pub fn get(&self, class: LotClass) -> LotVolume {
+        match class {
+            LotClass::Odd => self.odd,
+            LotClass::Round => self.round,
+            LotClass::Mixed => self.mixed,
+        }
+    }
+
+    /// Volume across all classifications.
+    pub fn total(&self) -> LotVolume {
+        LotVolume {
+            count: self.odd.count + self.round.count + self.mixed.count,
+            shares: self.odd.shares + self.round.shares + self.mixed.shares,
+        }
+    }
+
+    fn record(&mut self, class: LotClass, shares: u32) {
+        let volume = match class {
+            LotClass::Odd => &mut self.odd,
+            LotClass::Round => &mut self.round,
+            LotClass::Mixed => &mut self.mixed,
+        };
+        volume.count += 1;
+        volume.shares += shares as u64;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Security {
+    round_lot: Option<u32>,
+    round_lots_only: bool,
+    round: StockBook,
+    odd: StockBook,
+    added: LotStats,
+    executed: LotStats,
+}
+
+impl Security {
+
+    fn round_lot(&self) -> u32 { self.round_lot.unwrap_or(DEFAULT_ROUND_LOT) }
+
+    fn book_mut(&mut self, quantity: u32) -> &mut StockBook {
+        match LotClass::of(quantity, self.round_lot()) {
+            LotClass::Odd => &mut self.odd,
+            LotClass::Round | LotClass::Mixed => &mut self.round,
+        }
+    }
+
+    // Apply a change to the depth, moving the order between the odd and 
+    // round lot books when its remaining size crosses the round lot size.
+    fn apply(&mut self, change: &OrderChange) {
+
+        let after = change.order.quantity;
+        let before = (after as i64 - change.delta) as u32;
+        let odd = |q: u32| LotClass::of(q, self.round_lot()) == LotClass::Odd;
+
+        if before == 0 || after == 0 || odd(before) == odd(after) {
+            let quantity = if after == 0 { before } else { after };
+            self.book_mut(quantity).apply(change);
+        } else {
+            let mut order = change.order;
+            order.quantity = 0;
+            let removal = OrderChange { order, delta: -(before as i64), ..*change };
+            self.book_mut(before).apply(&removal);
+
+            order.quantity = after;
+            let addition = OrderChange { order, delta: after as i64, ..*change };
+            self.book_mut(after).apply(&addition);
+        }
+    }
+}
+
+/// Market-by-price book with odd lot orders kept apart from round 
+/// (and mixed) lot orders, using each security's round lot size 
+/// from the stock directory. Also counts orders added and shares executed 
+/// by lot classification.
+///
+/// Orders move between the two books as their remaining size changes:
+/// a 150-share order partially executed for 100 shares becomes odd lot depth.
+#[derive(Debug, Clone, Default)]
+pub struct LotBook {
+    securities: HashMap<u16, Security>,
+}
+
+impl LotBook {
+
+    /// Create an empty book.
+    pub fn new() -> Self { Self::default() }
+
+    /// Round lot size of a security.
+    pub fn round_lot(&self, stock_locate: u16) -> u32 {
+        self.securities.get(&stock_locate).map_or(DEFAULT_ROUND_LOT, Security::round_lot)
+    }
+
+    /// True if the security only accepts round lot orders.
+    pub fn round_lots_only(&self, stock_locate: u16) -> bool {
+        self.securities.get(&stock_locate).is_some_and(|s| s.round_lots_only)
+    }
+
+    /// Classify a quantity of shares in a security.
+    pub fn classify(&self, stock_locate: u16, quantity: u32) -> LotClass {
+        LotClass::of(quantity, self.round_lot(stock_locate))
+    }
+
+    /// Round and mixed lot depth of a security.
+    pub fn round_lots(&self, stock_locate: u16) -> Option<&StockBook> {
+        self.securities.get(&stock_locate).map(|s| &s.round)
+    }
+
+    /// Odd lot depth of a security.
+    pub fn odd_lots(&self, stock_locate: u16) -> Option<&StockBook> {
+        self.securities.get(&stock_locate).map(|s| &s.odd)
+    }
+
+    /// Orders added to the book, by the size of the order.
+    pub fn added(&self, stock_locate: u16) -> LotStats {
+        self.securities.get(&stock_locate).map(|s| s.added).unwrap_or_default()
+    }
+
+    /// Executions (displayed and non-displayed), by the size of the execution.
+    /// Non-printable executions and crosses are not counted.
+    pub fn executed(&self, stock_locate: u16) -> LotStats {
+        self.securities.get(&stock_locate).map(|s| s.executed).unwrap_or_default()
+    }
+}
+
+impl BookView for LotBook {
+
+    type Output = ();
+
+    fn apply(&mut self, _book: &Book, update: &OrderUpdate, msg: &ItchMessage) {
+
+        let locate = msg.metadata().stock_locate;
+
+        if let ItchMessage::StockDirectory { body, .. } = msg {
+            let security = self.securities.entry(locate).or_default();
+            security.round_lot = Some(body.round_lot_size).filter(|&size| size > 0);
+            security.round_lots_only = body.round_lots_only;
+        }
+
+        if update.is_empty() && !matches!(msg, ItchMessage::MatchTrade { .. }) {
+            return
+        }
+
+        let security = self.securities.entry(locate).or_default();
+        for change in update.iter() {
+            security.apply(change);
+            if change.delta > 0 {
+                let class = LotClass::of(change.order.quantity, security.round_lot());
+                security.added.record(class, change.order.quantity);
+            }
+        }
+
+        let executed = match msg {
+            ItchMessage::OrderExecuted { .. } => update.iter().next().map(|c| -c.delta as u32),
+            ItchMessage::OrderExecutedWithPrice { body, .. } => update.iter().next()
+                .filter(|_| body.printable)
+                .map(|c| -c.delta as u32),
+            ItchMessage::MatchTrade { body, .. } => Some(body.quantity),
+            _ => None,
+        };
+        if let Some(shares) = executed.filter(|&shares| shares > 0) {
+            let class = LotClass::of(shares, security.round_lot());
+            security.executed.record(class, shares);
+        }
+    }
+}
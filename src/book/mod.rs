@@ -0,0 +1,76 @@
+
+//! Order book reconstruction built on top of parsed `ItchMessage`s.
+
+/// Tracks live orders by their day-unique reference number.
+pub mod orders;
+/// Per-MPID (Level II montage) view of attributed liquidity.
+pub mod montage;
+/// Market-by-price book for every security.
+pub mod depth;
+/// Lock-free publication of book snapshots to reader threads.
+pub mod publish;
+/// Top-of-book publication to other processes through shared memory.
+pub mod shm;
+/// Level 1 quote updates derived from the book.
+pub mod quote;
+/// Level 2 (market-by-price) updates derived from the book.
+pub mod levels;
+/// Validation of the feed against the book being built.
+pub mod check;
+/// Paged order store for day-unique reference numbers.
+pub mod table;
+/// Odd, round and mixed lot classification of orders and executions.
+pub mod lots;
+/// Views that share a single book.
+pub mod view;
+
+pub use orders::{ Order, OrderChange, OrderUpdate, Orders };
+pub use montage::{ Level, Montage, MpidQuote };
+pub use depth::{ Book, LevelDelta, LevelUpdate, PriceLevel, StockBook };
+pub use publish::{ BookReader, LiveBook, SeqLock, Snapshot };
+pub use shm::{ TopOfBook, TopOfBookPublisher, TopOfBookReader };
+pub use quote::{ QuoteTracker, QuoteUpdate };
+pub use levels::{ DepthUpdate, LevelTracker };
+pub use check::{ Anomaly, AnomalyKind, BookChecker };
+pub use table::{ CompactOrder, OrderTable };
+pub use lots::{ LotBook, LotClass, LotStats, LotVolume };
+pub use view::{ BookView, OwnedView };
+
+use std::collections::HashMap;
+use nsdq_util::{ Mpid, Price, StockSymbol };
+
+use crate::msg::ItchMessage;
+
+/// MPID that Nasdaq recommends for displaying unattributed orders.
+pub fn unattributed() -> Mpid {
+    Mpid::from("NSDQ").expect("NSDQ is a valid MPID")
+}
+
+// Rebuild a price from its raw value (e.g. when used as a map key).
+// NOTE: `Price::new` would reject feed-valid prices above its OUCH limit.
+pub(crate) fn price(val: u32) -> Price<u32, 4> {
+    Price::<u32, 4>::parse(&val.to_be_bytes()).expect("Four bytes").1
+}
+
+// Symbols by locate, learned from directory records and order adds.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Symbols(HashMap<u16, StockSymbol>);
+
+impl Symbols {
+
+    pub(crate) fn update(&mut self, msg: &ItchMessage) {
+        match msg {
+            ItchMessage::StockDirectory { metadata, body } =>
+                { self.0.insert(metadata.stock_locate, body.stock); },
+            ItchMessage::OrderAdded { metadata, body } =>
+                { self.0.entry(metadata.stock_locate).or_insert(body.stock); },
+            ItchMessage::OrderAddedWithMpid { metadata, body } =>
+                { self.0.entry(metadata.stock_locate).or_insert(body.stock); },
+            _ => {},
+        }
+    }
+
+    pub(crate) fn get(&self, stock_locate: u16) -> Option<StockSymbol> {
+        self.0.get(&stock_locate).copied()
+    }
+}
@@ -0,0 +1,137 @@
+
+use std::collections::{ BTreeMap, HashMap };
+use nsdq_util::{ Mpid, Price };
+
+use crate::msg::{ ItchMessage, Side };
+use super::{ Book, BookView, OrderChange, OrderUpdate };
+
+/// Aggregate displayed size at a single price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+
+    /// Price of the level.
+    pub price: Price<u32, 4>,
+    /// Total displayed shares at this price.
+    pub size: u64,
+}
+
+/// A market participant's best bid and offer in a single security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MpidQuote {
+
+    /// Market participant displaying the quote.
+    pub mpid: Mpid,
+    /// Highest displayed bid, if any.
+    pub bid: Option<Level>,
+    /// Lowest displayed offer, if any.
+    pub ask: Option<Level>,
+}
+
+// Displayed size per price for a single MPID in a single security.
+#[derive(Debug, Clone)]
+struct Participant {
+    mpid: Mpid,
+    bids: BTreeMap<u32, u64>,
+    asks: BTreeMap<u32, u64>,
+}
+
+impl Participant {
+
+    fn new(mpid: Mpid) -> Self {
+        Self { mpid, bids: BTreeMap::new(), asks: BTreeMap::new() }
+    }
+
+    fn apply(&mut self, side: Side, price: u32, delta: i64) {
+
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+
+        let size = levels.entry(price).or_default();
+        *size = size.saturating_add_signed(delta);
+        if *size == 0 {
+            levels.remove(&price);
+        }
+    }
+
+    fn quote(&self) -> MpidQuote {
+
+        let level = |(price, size): (&u32, &u64)| Level {
+            price: super::price(*price),
+            size: *size,
+        };
+
+        MpidQuote {
+            mpid: self.mpid,
+            bid: self.bids.iter().next_back().map(level),
+            ask: self.asks.iter().next().map(level),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+/// Market-maker view of the book: for each security (by `stock_locate`),
+/// the best bid, best offer, and displayed size of every MPID.
+///
+/// Unattributed liquidity (`OrderAdded`) is rolled into the `NSDQ` MPID.
+/// Executions, cancels, deletes and replaces are resolved to the MPID of 
+/// the original order, since only the add messages carry attribution.
+#[derive(Debug, Clone, Default)]
+pub struct Montage {
+    stocks: HashMap<u16, HashMap<[u8; 4], Participant>>,
+}
+
+impl BookView for Montage {
+
+    type Output = ();
+
+    /// Messages that do not affect displayed orders are ignored.
+    fn apply(&mut self, _book: &Book, update: &OrderUpdate, _msg: &ItchMessage) {
+        for change in update.iter() {
+            self.change(change)
+        }
+    }
+}
+
+impl Montage {
+
+    /// Create an empty montage.
+    pub fn new() -> Self { Self::default() }
+
+    fn change(&mut self, change: &OrderChange) {
+
+        let order = &change.order;
+        let participants = self.stocks.entry(order.stock_locate).or_default();
+        let key = order.mpid.encode();
+        let participant = participants.entry(key)
+            .or_insert_with(|| Participant::new(order.mpid));
+
+        participant.apply(order.side, order.price.val(), change.delta);
+        if participant.is_empty() {
+            participants.remove(&key);
+        }
+    }
+
+    /// Quote for a single MPID, if it is displaying any orders in the issue.
+    pub fn quote(&self, stock_locate: u16, mpid: Mpid) -> Option<MpidQuote> {
+        self.stocks.get(&stock_locate)?
+            .get(&mpid.encode())
+            .map(Participant::quote)
+    }
+
+    /// Quotes of every MPID displaying orders in the issue, 
+    /// ordered by MPID.
+    pub fn quotes(&self, stock_locate: u16) -> Vec<MpidQuote> {
+
+        let mut quotes: Vec<MpidQuote> = self.stocks.get(&stock_locate)
+            .map(|p| p.values().map(Participant::quote).collect())
+            .unwrap_or_default();
+
+        quotes.sort_by_key(|q| q.mpid.encode());
+        quotes
+    }
+}
@@ -0,0 +1,186 @@
+
+use nsdq_util::{ Mpid, Price };
+
+use crate::msg::{ ItchMessage, Side };
+use super::OrderTable;
+
+/// Displayed order resting on the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Order {
+
+    /// Locate code of the security the order was placed for.
+    pub stock_locate: u16,
+    /// Buy/Sell indicator.
+    pub side: Side,
+    /// Displayed price of the order.
+    pub price: Price<u32, 4>,
+    /// Shares remaining on the book.
+    pub quantity: u32,
+    /// Attribution for the order (`NSDQ` when the order is unattributed).
+    pub mpid: Mpid,
+}
+
+/// Change to the displayed size of a single order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderChange {
+
+    /// Reference number of the order that changed.
+    pub order_ref_num: u64,
+    /// State of the order after the change.
+    /// A `quantity` of zero means the order has left the book.
+    pub order: Order,
+    /// Shares added to (positive) or removed from (negative) the order.
+    pub delta: i64,
+}
+
+impl OrderChange {
+
+    /// True if the order is no longer on the book after this change.
+    pub fn is_removal(&self) -> bool { self.order.quantity == 0 }
+}
+
+/// Order changes caused by a single message.
+/// `OrderReplaced` produces two changes (the removal, then the addition).
+/// An add onto a reference number that is still live is preceded by 
+/// the removal of the stale order, so a replace can produce three.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OrderUpdate([Option<OrderChange>; 3]);
+
+impl OrderUpdate {
+
+    fn push(&mut self, change: OrderChange) {
+        if let Some(slot) = self.0.iter_mut().find(|c| c.is_none()) {
+            *slot = Some(change)
+        }
+    }
+
+    /// Iterate over the changes in the order they were applied.
+    pub fn iter(&self) -> impl Iterator<Item = &OrderChange> {
+        self.0.iter().flatten()
+    }
+
+    /// True if the message did not affect any tracked order.
+    pub fn is_empty(&self) -> bool { self.0[0].is_none() }
+}
+
+/// Tracks every displayed order on the book by its `order_ref_num`,
+/// so that locate-only messages (executions, cancels, deletes, replaces)
+/// can be resolved to the side, price, and attribution of the original order.
+///
+/// Orders are kept in an `OrderTable`, indexed directly by reference number.
+#[derive(Debug, Clone, Default)]
+pub struct Orders {
+    live: OrderTable<Order>,
+}
+
+impl Orders {
+
+    /// Create an empty order store.
+    pub fn new() -> Self { Self::default() }
+
+    /// Look up a live order by reference number.
+    pub fn get(&self, order_ref_num: u64) -> Option<&Order> {
+        self.live.get(order_ref_num)
+    }
+
+    /// Number of orders currently on the book.
+    pub fn len(&self) -> usize { self.live.len() }
+
+    /// True if there are no orders on the book.
+    pub fn is_empty(&self) -> bool { self.live.is_empty() }
+
+    /// Apply an order-related message to the store.
+    /// Messages for unknown reference numbers and non-order messages 
+    /// produce an empty update.
+    pub fn apply(&mut self, msg: &ItchMessage) -> OrderUpdate {
+
+        let mut update = OrderUpdate::default();
+        let locate = msg.metadata().stock_locate;
+
+        match msg {
+            ItchMessage::OrderAdded { body, .. } => {
+                let order = Order {
+                    stock_locate: locate,
+                    side: body.side,
+                    price: body.price,
+                    quantity: body.quantity,
+                    mpid: super::unattributed(),
+                };
+                self.add(&mut update, body.order_ref_num, order);
+            },
+            ItchMessage::OrderAddedWithMpid { body, .. } => {
+                let order = Order {
+                    stock_locate: locate,
+                    side: body.side,
+                    price: body.price,
+                    quantity: body.quantity,
+                    mpid: body.mpid,
+                };
+                self.add(&mut update, body.order_ref_num, order);
+            },
+            ItchMessage::OrderExecuted { body, .. } => {
+                if let Some(c) = self.reduce(body.order_ref_num, body.quantity) {
+                    update.push(c)
+                }
+            },
+            ItchMessage::OrderExecutedWithPrice { body, .. } => {
+                if let Some(c) = self.reduce(body.order_ref_num, body.quantity) {
+                    update.push(c)
+                }
+            },
+            ItchMessage::OrderCanceled { body, .. } => {
+                if let Some(c) = self.reduce(body.order_ref_num, body.quantity) {
+                    update.push(c)
+                }
+            },
+            ItchMessage::OrderDeleted { body, .. } => {
+                if let Some(c) = self.reduce(body.order_ref_num, u32::MAX) {
+                    update.push(c)
+                }
+            },
+            ItchMessage::OrderReplaced { body, .. } => {
+                // Side, stock and attribution carry over from the original.
+                if let Some(removed) = self.reduce(body.old_ref_num, u32::MAX) {
+                    let order = Order {
+                        price: body.price,
+                        quantity: body.quantity,
+                        ..removed.order
+                    };
+                    update.push(removed);
+                    self.add(&mut update, body.new_ref_num, order);
+                }
+            },
+            _ => {}
+        }
+
+        update
+    }
+
+    // Adds the order, removing any live order with the same reference number
+    // so that its shares don't linger in views built from the changes.
+    fn add(&mut self, update: &mut OrderUpdate, order_ref_num: u64, order: Order) {
+        if let Some(stale) = self.live.insert(order_ref_num, order) {
+            update.push(OrderChange { 
+                order_ref_num, 
+                order: Order { quantity: 0, ..stale }, 
+                delta: -(stale.quantity as i64),
+            });
+        }
+        update.push(OrderChange { order_ref_num, order, delta: order.quantity as i64 });
+    }
+
+    // Removes up to `quantity` shares, dropping the order once it is empty.
+    fn reduce(&mut self, order_ref_num: u64, quantity: u32) -> Option<OrderChange> {
+
+        let order = self.live.get_mut(order_ref_num)?;
+        let removed = quantity.min(order.quantity);
+        order.quantity -= removed;
+        let order = *order;
+
+        if order.quantity == 0 {
+            self.live.remove(order_ref_num);
+        }
+
+        Some(OrderChange { order_ref_num, order, delta: -(removed as i64) })
+    }
+}
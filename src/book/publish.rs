@@ -0,0 +1,190 @@
+
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicU64, Ordering, fence };
+
+use crate::msg::ItchMessage;
+use super::{ Book, BookView, OrderUpdate, PriceLevel, StockBook };
+
+/// Single-writer sequence lock for `Copy` data.
+///
+/// The writer never waits: it bumps the sequence to odd, writes, 
+/// and bumps it back to even. Readers retry until they copy the value 
+/// without observing a write in progress.
+pub struct SeqLock<T: Copy> {
+    seq: AtomicU64,
+    data: UnsafeCell<T>,
+}
+
+// SAFETY: Readers only return copies validated against the sequence,
+// and `write` is restricted to a single writer by its callers.
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+
+impl<T: Copy> SeqLock<T> {
+
+    /// Create a lock holding `value`.
+    pub fn new(value: T) -> Self {
+        Self { seq: AtomicU64::new(0), data: UnsafeCell::new(value) }
+    }
+
+    /// Number of completed writes.
+    pub fn version(&self) -> u64 { self.seq.load(Ordering::Acquire) / 2 }
+
+    /// Copy out a consistent value.
+    pub fn read(&self) -> T {
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                std::hint::spin_loop();
+                continue
+            }
+
+            // SAFETY: A torn copy is discarded when the sequence changes.
+            let value = unsafe { std::ptr::read_volatile(self.data.get()) };
+            fence(Ordering::Acquire);
+
+            if self.seq.load(Ordering::Relaxed) == before {
+                return value
+            }
+        }
+    }
+
+    /// Replace the value.
+    ///
+    /// # Safety
+    /// Must not be called from more than one thread at a time.
+    pub unsafe fn write(&self, value: T) {
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        unsafe { std::ptr::write_volatile(self.data.get(), value) };
+        self.seq.store(seq + 2, Ordering::Release);
+    }
+}
+
+/// Consistent copy of the top `N` price levels of a security,
+/// along with the last message that changed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot<const N: usize> {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Best bid levels, highest first.
+    pub bids: [Option<PriceLevel>; N],
+    /// Best ask levels, lowest first.
+    pub asks: [Option<PriceLevel>; N],
+    /// Timestamp (nanoseconds since midnight) of the last applied message.
+    pub nanos: u64,
+    /// Tracking number of the last applied message.
+    pub tracking_number: u16,
+}
+
+impl<const N: usize> Snapshot<N> {
+
+    fn empty(stock_locate: u16) -> Self {
+        Self {
+            stock_locate,
+            bids: [None; N],
+            asks: [None; N],
+            nanos: 0,
+            tracking_number: 0,
+        }
+    }
+
+    fn capture(stock_locate: u16, book: Option<&StockBook>, msg: &ItchMessage) -> Self {
+
+        let meta = msg.metadata();
+        let mut snapshot = Self {
+            nanos: meta.nanos,
+            tracking_number: meta.tracking_number,
+            ..Self::empty(stock_locate)
+        };
+
+        if let Some(book) = book {
+            for (slot, level) in snapshot.bids.iter_mut().zip(book.bids()) {
+                *slot = Some(level)
+            }
+            for (slot, level) in snapshot.asks.iter_mut().zip(book.asks()) {
+                *slot = Some(level)
+            }
+        }
+        snapshot
+    }
+
+    /// Highest bid.
+    pub fn best_bid(&self) -> Option<PriceLevel> { self.bids[0] }
+
+    /// Lowest offer.
+    pub fn best_ask(&self) -> Option<PriceLevel> { self.asks[0] }
+}
+
+type Slots<const N: usize> = Arc<[SeqLock<Snapshot<N>>]>;
+
+/// Publishes a `Snapshot` of every security the book changes,
+/// for any number of `BookReader`s on other threads.
+///
+/// Only the owning (feed) thread can apply messages, 
+/// so each per-security slot always has a single writer.
+pub struct LiveBook<const N: usize> {
+    slots: Slots<N>,
+}
+
+impl<const N: usize> LiveBook<N> {
+
+    /// Create empty snapshots for locate codes up to `max_locate`.
+    pub fn new(max_locate: u16) -> Self {
+        let slots = (0..=max_locate)
+            .map(|l| SeqLock::new(Snapshot::empty(l)))
+            .collect();
+        Self { slots }
+    }
+
+    /// Create a reader handle that can be sent to other threads.
+    pub fn reader(&self) -> BookReader<N> {
+        BookReader { slots: self.slots.clone() }
+    }
+}
+
+impl<const N: usize> BookView for LiveBook<N> {
+
+    type Output = ();
+
+    /// Publishes snapshots of every security the message changed.
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) {
+
+        let mut last = None;
+        for change in update.iter() {
+            let locate = change.order.stock_locate;
+            if last == Some(locate) { continue }
+            last = Some(locate);
+
+            if let Some(slot) = self.slots.get(locate as usize) {
+                let snapshot = Snapshot::capture(locate, book.stock(locate), msg);
+                // SAFETY: `&mut self` makes this the only writer.
+                unsafe { slot.write(snapshot) }
+            }
+        }
+    }
+}
+
+/// Lock-free read access to the snapshots published by a `LiveBook`.
+#[derive(Clone)]
+pub struct BookReader<const N: usize> {
+    slots: Slots<N>,
+}
+
+impl<const N: usize> BookReader<N> {
+
+    /// Latest snapshot for a security, 
+    /// or `None` if nothing has been published for it.
+    pub fn snapshot(&self, stock_locate: u16) -> Option<Snapshot<N>> {
+        let slot = self.slots.get(stock_locate as usize)?;
+        if slot.version() == 0 { return None }
+        Some(slot.read())
+    }
+
+    /// Number of times the snapshot of a security has been published.
+    pub fn version(&self, stock_locate: u16) -> u64 {
+        self.slots.get(stock_locate as usize).map_or(0, SeqLock::version)
+    }
+}
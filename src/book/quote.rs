@@ -0,0 +1,76 @@
+
+use std::collections::HashMap;
+use nsdq_util::{ NaiveTime, StockSymbol };
+
+use crate::msg::ItchMessage;
+use super::{ Book, BookView, Level, OrderUpdate, PriceLevel, Symbols };
+
+/// New best bid and offer of a security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteUpdate {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Symbol of the security.
+    pub stock: StockSymbol,
+    /// Best bid, if any bids are displayed.
+    pub bid: Option<Level>,
+    /// Best offer, if any offers are displayed.
+    pub ask: Option<Level>,
+    /// Time of the message that changed the quote.
+    pub timestamp: NaiveTime,
+}
+
+/// Level 1 view of the book: emits a `QuoteUpdate` 
+/// only when a message changes a security's best bid or offer.
+#[derive(Debug, Clone, Default)]
+pub struct QuoteTracker {
+    symbols: Symbols,
+    quotes: HashMap<u16, (Option<Level>, Option<Level>)>,
+}
+
+impl BookView for QuoteTracker {
+
+    type Output = Option<QuoteUpdate>;
+
+    /// Returns the new quote if the BBO changed.
+    /// All changes caused by one message (e.g. both halves of a replace) 
+    /// are coalesced into a single update.
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) -> Option<QuoteUpdate> {
+
+        self.symbols.update(msg);
+
+        // Every order change of a message belongs to a single security.
+        let locate = update.iter().next()?.order.stock_locate;
+
+        let level = |l: PriceLevel| Level { price: l.price, size: l.size };
+        let stock = book.stock(locate);
+        let quote = (
+            stock.and_then(|s| s.best_bid()).map(level),
+            stock.and_then(|s| s.best_ask()).map(level),
+        );
+
+        let previous = self.quotes.insert(locate, quote).unwrap_or_default();
+        if previous == quote { return None }
+
+        Some(QuoteUpdate {
+            stock_locate: locate,
+            // Orders are only known from adds, which carry the symbol.
+            stock: self.symbols.get(locate)?,
+            bid: quote.0,
+            ask: quote.1,
+            timestamp: msg.metadata().timestamp(),
+        })
+    }
+}
+
+impl QuoteTracker {
+
+    /// Create a tracker with no quotes.
+    pub fn new() -> Self { Self::default() }
+
+    /// Current best bid and offer of a security.
+    pub fn quote(&self, stock_locate: u16) -> (Option<Level>, Option<Level>) {
+        self.quotes.get(&stock_locate).copied().unwrap_or_default()
+    }
+}
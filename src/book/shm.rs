@@ -0,0 +1,339 @@
+
+//! Top-of-book records in a memory-mapped file, for consumers in other 
+//! processes (in any language) on the same host.
+//!
+//! # Layout
+//! All integers are in native byte order (little-endian on x86-64/AArch64).
+//! Prices are unsigned integers with 4 implied decimal places, 
+//! and a price of zero means the side (or last trade) is absent.
+//!
+//! The file starts with a 64-byte header:
+//!
+//! | Offset | Type      | Field                                   |
+//! |--------|-----------|-----------------------------------------|
+//! | 0      | `[u8; 8]` | Magic: `LITCHBBO`                       |
+//! | 8      | `u32`     | Layout version (currently 1)            |
+//! | 12     | `u32`     | Record size in bytes (64)               |
+//! | 16     | `u32`     | Record count (highest locate code + 1)  |
+//! | 20     | -         | Reserved (zero)                         |
+//!
+//! It is followed by one 64-byte record per locate code, 
+//! so the record for locate `L` starts at byte `64 + 64 * L`:
+//!
+//! | Offset | Type  | Field                                            |
+//! |--------|-------|--------------------------------------------------|
+//! | 0      | `u64` | Sequence (seqlock version, odd while writing)    |
+//! | 8      | `u64` | Bid size (shares at the best bid)                |
+//! | 16     | `u64` | Ask size (shares at the best ask)                |
+//! | 24     | `u32` | Bid price                                        |
+//! | 28     | `u32` | Ask price                                        |
+//! | 32     | `u32` | Last trade price                                 |
+//! | 36     | `u32` | Last trade size                                  |
+//! | 40     | `u64` | Last trade time (nanoseconds since midnight)     |
+//! | 48     | `u64` | Last update time (nanoseconds since midnight)    |
+//! | 56     | `u16` | Locate code                                      |
+//! | 58     | `u8`  | Trading state (ASCII `H`, `P`, `Q`, `T`, or 0)   |
+//! | 59     | -     | Reserved (zero)                                  |
+//!
+//! # Reading a record
+//! 1. Read the sequence (acquire); if it is odd, a write is in progress.
+//! 2. Copy the rest of the record.
+//! 3. Read the sequence again; if it changed, discard the copy and retry.
+//!
+//! A sequence of zero means the record has never been written.
+//!
+//! A new publisher replaces the file rather than truncating it in place,
+//! so readers must reopen the path to follow a restarted publisher.
+
+use std::fs::{ File, OpenOptions };
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{ AtomicU64, Ordering, fence };
+
+use memmap2::{ Mmap, MmapMut };
+use nsdq_util::Price;
+
+use crate::msg::{ ItchMessage, TradingState };
+use super::{ Book, BookView, OrderUpdate };
+
+const MAGIC: [u8; 8] = *b"LITCHBBO";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 64;
+const RECORD_LEN: usize = 64;
+
+// In-memory image of a record, following the documented layout.
+#[repr(C, align(64))]
+#[derive(Debug, Clone, Copy, Default)]
+struct Record {
+    seq: u64,
+    bid_size: u64,
+    ask_size: u64,
+    bid_price: u32,
+    ask_price: u32,
+    last_price: u32,
+    last_size: u32,
+    last_nanos: u64,
+    nanos: u64,
+    stock_locate: u16,
+    state: u8,
+    reserved: [u8; 5],
+}
+
+const _: () = assert!(std::mem::size_of::<Record>() == RECORD_LEN);
+
+/// Top-of-book for a single security, as read from the shared file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopOfBook {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Best bid price and size.
+    pub bid: Option<(Price<u32, 4>, u64)>,
+    /// Best ask price and size.
+    pub ask: Option<(Price<u32, 4>, u64)>,
+    /// Price and size of the last printable trade.
+    pub last_trade: Option<(Price<u32, 4>, u32)>,
+    /// Time of the last printable trade (nanoseconds since midnight).
+    pub last_trade_nanos: u64,
+    /// Current trading state, if a trading action has been received.
+    pub state: Option<TradingState>,
+    /// Time of the last update (nanoseconds since midnight).
+    pub nanos: u64,
+}
+
+impl From<Record> for TopOfBook {
+    fn from(r: Record) -> Self {
+
+        let price = super::price;
+        let state = TradingState::parse(&[r.state]).ok().map(|(_, s)| s);
+
+        Self {
+            stock_locate: r.stock_locate,
+            bid: (r.bid_price != 0).then(|| (price(r.bid_price), r.bid_size)),
+            ask: (r.ask_price != 0).then(|| (price(r.ask_price), r.ask_size)),
+            last_trade: (r.last_price != 0).then(|| (price(r.last_price), r.last_size)),
+            last_trade_nanos: r.last_nanos,
+            state,
+            nanos: r.nanos,
+        }
+    }
+}
+
+// Copy everything but the sequence, which is only accessed atomically.
+unsafe fn copy_payload(src: *const Record, dst: *mut Record) {
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            (src as *const u8).add(8), 
+            (dst as *mut u8).add(8), 
+            RECORD_LEN - 8,
+        );
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Writes each security's top-of-book into a memory-mapped file 
+/// (see the module docs for the layout).
+pub struct TopOfBookPublisher {
+    map: MmapMut,
+    records: Vec<Record>,
+}
+
+impl TopOfBookPublisher {
+
+    /// Create the file at `path`, with room for locate codes up to `max_locate`.
+    ///
+    /// An existing file is never truncated, since readers may still have it 
+    /// mapped: the new file is written beside it and renamed over it.
+    /// Readers of the old file keep a valid (but stale) mapping, 
+    /// and must reopen `path` to follow the new publisher.
+    pub fn create(path: impl AsRef<Path>, max_locate: u16) -> io::Result<Self> {
+
+        let path = path.as_ref();
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(format!(".{}.tmp", std::process::id()));
+
+        let count = max_locate as usize + 1;
+        let map = Self::create_map(Path::new(&temp), count)
+            .and_then(|map| std::fs::rename(&temp, path).map(|_| map))
+            .inspect_err(|_| { let _ = std::fs::remove_file(&temp); })?;
+
+        Ok(Self { map, records: vec![Record::default(); count] })
+    }
+
+    // Create a private file with the header written, ready to be published.
+    fn create_map(path: &Path, count: usize) -> io::Result<MmapMut> {
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((HEADER_LEN + count * RECORD_LEN) as u64)?;
+
+        // SAFETY: No reader can have the file mapped until it is renamed.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        map[..8].copy_from_slice(&MAGIC);
+        map[8..12].copy_from_slice(&VERSION.to_ne_bytes());
+        map[12..16].copy_from_slice(&(RECORD_LEN as u32).to_ne_bytes());
+        map[16..20].copy_from_slice(&(count as u32).to_ne_bytes());
+        Ok(map)
+    }
+
+    // Rewrite the record of the security a message affected.
+    fn publish(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) {
+
+        let meta = msg.metadata();
+
+        let locate = update.iter().next()
+            .map_or(meta.stock_locate, |c| c.order.stock_locate);
+        let Some(record) = self.records.get_mut(locate as usize) else { return };
+        let mut changed = !update.is_empty();
+
+        // Last sale comes from the executed order, or from the trade itself.
+        let trade = match msg {
+            ItchMessage::OrderExecuted { body, .. } => {
+                update.iter().next().map(|c| (c.order.price, body.quantity))
+            },
+            ItchMessage::OrderExecutedWithPrice { body, .. } if body.printable => {
+                Some((body.price, body.quantity))
+            },
+            ItchMessage::MatchTrade { body, .. } => Some((body.price, body.quantity)),
+            ItchMessage::CrossTrade { body, .. } if body.quantity > 0 => {
+                Some((body.price, body.quantity))
+            },
+            _ => None,
+        };
+        if let Some((price, size)) = trade {
+            record.last_price = price.val();
+            record.last_size = size;
+            record.last_nanos = meta.nanos;
+            changed = true;
+        }
+
+        if let ItchMessage::TradingAction { body, .. } = msg {
+            record.state = body.state.encode()[0];
+            changed = true;
+        }
+
+        if !changed { return }
+
+        let stock = book.stock(locate);
+        let bid = stock.and_then(|s| s.best_bid());
+        let ask = stock.and_then(|s| s.best_ask());
+        record.bid_price = bid.map_or(0, |l| l.price.val());
+        record.bid_size = bid.map_or(0, |l| l.size);
+        record.ask_price = ask.map_or(0, |l| l.price.val());
+        record.ask_size = ask.map_or(0, |l| l.size);
+        record.stock_locate = locate;
+        record.nanos = meta.nanos;
+
+        let record = *record;
+        self.write(locate as usize, record);
+    }
+
+    fn write(&mut self, index: usize, record: Record) {
+
+        let offset = HEADER_LEN + index * RECORD_LEN;
+        let ptr = self.map[offset..offset + RECORD_LEN].as_mut_ptr() as *mut Record;
+
+        // SAFETY: The map is page-aligned, so every record is 64-byte aligned,
+        // and this publisher is the only writer.
+        unsafe {
+            let seq = &*(ptr as *const AtomicU64);
+            let version = seq.load(Ordering::Relaxed);
+            seq.store(version + 1, Ordering::Relaxed);
+            fence(Ordering::Release);
+
+            copy_payload(&record, ptr);
+            seq.store(version + 2, Ordering::Release);
+        }
+    }
+
+    /// Flush the mapped file to disk (not needed for readers on this host).
+    pub fn flush(&self) -> io::Result<()> { self.map.flush() }
+}
+
+impl BookView for TopOfBookPublisher {
+
+    type Output = ();
+
+    /// Rewrites the record of the security the message affected.
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) {
+        self.publish(book, update, msg)
+    }
+}
+
+/// Reads top-of-book records published by a `TopOfBookPublisher`,
+/// possibly in another process.
+pub struct TopOfBookReader {
+    map: Mmap,
+    count: usize,
+}
+
+impl TopOfBookReader {
+
+    /// Map a published file and validate its header.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+
+        let file = File::open(path)?;
+        // SAFETY: Concurrent writes are guarded by the record seqlocks.
+        let map = unsafe { Mmap::map(&file)? };
+
+        if map.len() < HEADER_LEN || map[..8] != MAGIC {
+            return Err(invalid("Not a litch top-of-book file"))
+        }
+        let field = |at: usize| u32::from_ne_bytes(map[at..at + 4].try_into().unwrap());
+        if field(8) != VERSION || field(12) as usize != RECORD_LEN {
+            return Err(invalid("Unsupported top-of-book layout"))
+        }
+
+        let count = field(16) as usize;
+        if map.len() < HEADER_LEN + count * RECORD_LEN {
+            return Err(invalid("Top-of-book file is truncated"))
+        }
+
+        Ok(Self { map, count })
+    }
+
+    /// Number of records (highest locate code + 1).
+    pub fn len(&self) -> usize { self.count }
+
+    /// True if the file holds no records.
+    pub fn is_empty(&self) -> bool { self.count == 0 }
+
+    /// Read a consistent copy of a security's record,
+    /// or `None` if it has never been written.
+    pub fn get(&self, stock_locate: u16) -> Option<TopOfBook> {
+
+        let index = stock_locate as usize;
+        if index >= self.count { return None }
+
+        let offset = HEADER_LEN + index * RECORD_LEN;
+        let ptr = self.map[offset..offset + RECORD_LEN].as_ptr() as *const Record;
+
+        // SAFETY: In bounds and 64-byte aligned; torn copies are discarded.
+        unsafe {
+            let seq = &*(ptr as *const AtomicU64);
+            loop {
+                let before = seq.load(Ordering::Acquire);
+                if before == 0 { return None }
+                if before & 1 == 1 {
+                    std::hint::spin_loop();
+                    continue
+                }
+
+                let mut record = Record::default();
+                copy_payload(ptr, &mut record);
+                fence(Ordering::Acquire);
+
+                if seq.load(Ordering::Relaxed) == before {
+                    return Some(record.into())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+
+use std::collections::HashMap;
+use nsdq_util::Price;
+
+use crate::msg::Side;
+
+/// Reference numbers per page (a power of two).
+const PAGE_BITS: u32 = 12;
+const PAGE_LEN: usize = 1 << PAGE_BITS;
+
+/// Reference numbers at or above this are kept in an overflow map 
+/// rather than growing the page directory, which is then at most 
+/// 2^22 slots (32 MiB). A full trading day assigns well under 2^34.
+const MAX_DIRECT: u64 = 1 << 34;
+
+/// Compact record of a live order (12 bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactOrder {
+
+    /// Price of the order.
+    pub price: Price<u32, 4>,
+    /// Shares remaining on the book.
+    pub quantity: u32,
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Buy or sell.
+    pub side: Side,
+}
+
+#[derive(Clone)]
+struct Page<T> {
+    slots: Box<[Option<T>]>,
+    live: u32,
+}
+
+impl<T: Copy> Page<T> {
+
+    fn new() -> Box<Self> {
+        Box::new(Self { slots: vec![None; PAGE_LEN].into_boxed_slice(), live: 0 })
+    }
+}
+
+/// Order store for day-unique, mostly increasing reference numbers.
+///
+/// Orders live in fixed-size pages indexed directly by reference number, 
+/// so lookups are two array accesses and records stay contiguous.
+/// Pages are allocated on first use and freed once all their orders are 
+/// gone, so page memory follows the orders still on the book rather than 
+/// the number assigned during the day. The page directory itself grows 
+/// with the highest reference number (8 bytes per 4096 numbers).
+/// Reference numbers beyond the direct range fall back to a `HashMap`.
+///
+/// Records are `CompactOrder`s by default; `Orders` keeps full `Order`s 
+/// (with attribution) in the same layout.
+#[derive(Clone)]
+pub struct OrderTable<T = CompactOrder> {
+    pages: Vec<Option<Box<Page<T>>>>,
+    overflow: HashMap<u64, T>,
+    frontier: usize,
+    len: usize,
+}
+
+impl<T> Default for OrderTable<T> {
+
+    fn default() -> Self {
+        Self { pages: Vec::new(), overflow: HashMap::new(), frontier: 0, len: 0 }
+    }
+}
+
+impl<T: Copy> OrderTable<T> {
+
+    /// Create an empty table.
+    pub fn new() -> Self { Self::default() }
+
+    /// Create a table whose page directory already covers 
+    /// reference numbers up to `max_ref`.
+    pub fn with_max_ref(max_ref: u64) -> Self {
+        let mut table = Self::new();
+        let pages = (max_ref.min(MAX_DIRECT - 1) >> PAGE_BITS) as usize + 1;
+        table.pages.resize_with(pages, || None);
+        table
+    }
+
+    fn split(order_ref_num: u64) -> (usize, usize) {
+        ((order_ref_num >> PAGE_BITS) as usize, (order_ref_num as usize) & (PAGE_LEN - 1))
+    }
+
+    /// Number of live orders.
+    pub fn len(&self) -> usize { self.len }
+
+    /// True if there are no live orders.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Number of pages currently allocated.
+    pub fn pages(&self) -> usize {
+        self.pages.iter().filter(|p| p.is_some()).count()
+    }
+
+    /// Look up a live order.
+    pub fn get(&self, order_ref_num: u64) -> Option<&T> {
+
+        if order_ref_num >= MAX_DIRECT {
+            return self.overflow.get(&order_ref_num)
+        }
+        let (page, slot) = Self::split(order_ref_num);
+        self.pages.get(page)?.as_ref()?.slots[slot].as_ref()
+    }
+
+    /// Look up a live order for modification.
+    pub fn get_mut(&mut self, order_ref_num: u64) -> Option<&mut T> {
+
+        if order_ref_num >= MAX_DIRECT {
+            return self.overflow.get_mut(&order_ref_num)
+        }
+        let (page, slot) = Self::split(order_ref_num);
+        self.pages.get_mut(page)?.as_mut()?.slots[slot].as_mut()
+    }
+
+    /// Insert an order, returning the order it replaced (if any).
+    pub fn insert(&mut self, order_ref_num: u64, order: T) -> Option<T> {
+
+        let previous = if order_ref_num >= MAX_DIRECT {
+            self.overflow.insert(order_ref_num, order)
+        } else {
+            let (page, slot) = Self::split(order_ref_num);
+            if page >= self.pages.len() {
+                self.pages.resize_with(page + 1, || None);
+            }
+            self.frontier = self.frontier.max(page);
+
+            let page = self.pages[page].get_or_insert_with(Page::new);
+            let previous = page.slots[slot].replace(order);
+            if previous.is_none() { page.live += 1 }
+            previous
+        };
+
+        if previous.is_none() { self.len += 1 }
+        previous
+    }
+
+    /// Remove an order, returning it if it was live.
+    pub fn remove(&mut self, order_ref_num: u64) -> Option<T> {
+
+        let removed = if order_ref_num >= MAX_DIRECT {
+            self.overflow.remove(&order_ref_num)
+        } else {
+            let (index, slot) = Self::split(order_ref_num);
+            let entry = self.pages.get_mut(index)?;
+            let page = entry.as_mut()?;
+            let removed = page.slots[slot].take();
+            if removed.is_some() { page.live -= 1 }
+
+            // Keep the page being filled, which would otherwise 
+            // be reallocated by the next add.
+            if page.live == 0 && index < self.frontier {
+                *entry = None;
+            }
+            removed
+        };
+
+        if removed.is_some() { self.len -= 1 }
+        removed
+    }
+}
+
+impl OrderTable {
+
+    /// Remove up to `quantity` shares, dropping the order once it is empty.
+    /// Returns the order's state afterwards and the shares removed.
+    pub fn reduce(&mut self, order_ref_num: u64, quantity: u32) -> Option<(CompactOrder, u32)> {
+
+        let order = self.get_mut(order_ref_num)?;
+        let removed = quantity.min(order.quantity);
+        order.quantity -= removed;
+        let order = *order;
+
+        if order.quantity == 0 {
+            self.remove(order_ref_num);
+        }
+        Some((order, removed))
+    }
+}
+
+impl<T> std::fmt::Debug for OrderTable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrderTable")
+            .field("len", &self.len)
+            .field("pages", &self.pages.iter().filter(|p| p.is_some()).count())
+            .field("overflow", &self.overflow.len())
+            .finish()
+    }
+}
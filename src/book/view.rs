@@ -0,0 +1,65 @@
+
+use std::ops::{ Deref, DerefMut };
+
+use crate::msg::ItchMessage;
+use super::{ Book, OrderUpdate };
+
+/// A view derived from the book, such as quotes, depth or a tracker.
+///
+/// Views don't keep orders of their own: each message is applied to a
+/// single `Book`, and the book and the `OrderUpdate` it returned are then
+/// passed to every view, so running several views costs one copy of the
+/// live orders. `OwnedView` pairs a view with its own book for when
+/// it is the only consumer of the feed.
+pub trait BookView {
+
+    /// What the view reports for each message.
+    type Output;
+
+    /// Apply a message that has just been applied to `book`,
+    /// where it caused `update`.
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) -> Self::Output;
+
+    /// Give the view a book of its own.
+    fn owned(self) -> OwnedView<Self> where Self: Sized {
+        OwnedView::new(self)
+    }
+}
+
+/// A view that maintains its own `Book`.
+/// Dereferences to the view.
+#[derive(Debug, Clone, Default)]
+pub struct OwnedView<V> {
+    book: Book,
+    view: V,
+}
+
+impl<V: BookView> OwnedView<V> {
+
+    /// Pair a view with an empty book.
+    pub fn new(view: V) -> Self { Self { book: Book::new(), view } }
+
+    /// Apply a message to the book, then to the view.
+    pub fn update(&mut self, msg: &ItchMessage) -> V::Output {
+        let update = self.book.update(msg);
+        self.view.apply(&self.book, &update, msg)
+    }
+
+    /// The view's book.
+    pub fn book(&self) -> &Book { &self.book }
+
+    /// Separate the view from its book.
+    pub fn into_inner(self) -> V { self.view }
+}
+
+impl<V> Deref for OwnedView<V> {
+
+    type Target = V;
+
+    fn deref(&self) -> &V { &self.view }
+}
+
+impl<V> DerefMut for OwnedView<V> {
+
+    fn deref_mut(&mut self) -> &mut V { &mut self.view }
+}
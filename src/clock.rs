@@ -0,0 +1,136 @@
+
+use std::path::Path;
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use chrono::{ DateTime, Datelike, NaiveDate, TimeZone, Utc };
+use chrono_tz::{ America::New_York, Tz };
+use nsdq_util::NaiveTime;
+
+use crate::msg::ItchMetadata;
+
+/// One second, in the nanoseconds since midnight of ITCH timestamps.
+pub const SECOND: u64 = 1_000_000_000;
+/// One minute, in nanoseconds.
+pub const MINUTE: u64 = 60 * SECOND;
+/// One hour, in nanoseconds.
+pub const HOUR: u64 = 60 * MINUTE;
+
+/// Start of regular market hours (9:30), in nanoseconds since midnight.
+/// Only a fallback: the day's `SystemEvent`s are authoritative.
+pub const MARKET_OPEN: u64 = 9 * HOUR + 30 * MINUTE;
+/// End of regular market hours (16:00) on a full trading day.
+pub const MARKET_CLOSE: u64 = 16 * HOUR;
+
+/// Trading date that anchors ITCH timestamps to absolute instants.
+///
+/// ITCH timestamps count nanoseconds elapsed since midnight (Eastern time) 
+/// of the trading day, so conversions add the elapsed time to that midnight.
+/// This stays correct across daylight saving transitions, 
+/// when wall-clock time and elapsed time disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TradingDate {
+    date: NaiveDate,
+}
+
+impl TradingDate {
+
+    /// Anchor timestamps to the given calendar date (Eastern time).
+    pub fn new(date: NaiveDate) -> Self { Self { date } }
+
+    /// Anchor timestamps to the given year, month and day.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, day).map(Self::new)
+    }
+
+    /// Infer the trading date from a file name. 
+    /// Recognizes `MMDDYYYY` (as used by Nasdaq's historical files, 
+    /// e.g. `01302019.NASDAQ_ITCH50`), `YYYYMMDD` and `YYYY-MM-DD`.
+    pub fn from_file_name(path: impl AsRef<Path>) -> Option<Self> {
+
+        let name = path.as_ref().file_name()?.to_str()?;
+        let bytes = name.as_bytes();
+        let digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+
+        // Candidates must not be embedded in a longer run of digits.
+        (0..bytes.len())
+            .filter(|&i| digit(i) && (i == 0 || !digit(i - 1)))
+            .find_map(|i| {
+                let dashed = name.get(i..i + 10)
+                    .filter(|_| !digit(i + 10))
+                    .and_then(|s| Self::parse_date(s, &["%Y-%m-%d"]));
+                let packed = name.get(i..i + 8)
+                    .filter(|_| !digit(i + 8))
+                    .and_then(|s| Self::parse_date(s, &["%m%d%Y", "%Y%m%d"]));
+                dashed.or(packed)
+            })
+    }
+
+    fn parse_date(s: &str, fmts: &[&str]) -> Option<Self> {
+        fmts.iter()
+            .filter_map(|f| NaiveDate::parse_from_str(s, f).ok())
+            .find(|d| (1990..=2200).contains(&d.year()))
+            .map(Self::new)
+    }
+
+    /// Infer the trading date from the time data was captured,
+    /// taking the calendar date in New York at that instant.
+    pub fn from_capture(captured: SystemTime) -> Self {
+
+        let nanos = captured.duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as i64)
+            .unwrap_or_default();
+
+        let date = Utc.timestamp_nanos(nanos)
+            .with_timezone(&New_York)
+            .date_naive();
+
+        Self::new(date)
+    }
+
+    /// Calendar date of the trading day.
+    pub fn date(&self) -> NaiveDate { self.date }
+
+    /// Midnight (Eastern) at the start of the trading day.
+    pub fn midnight(&self) -> DateTime<Tz> {
+        // DST transitions happen at 2 a.m., so midnight always exists.
+        New_York.from_local_datetime(&self.date.and_time(NaiveTime::MIN))
+            .earliest()
+            .expect("Midnight exists in America/New_York")
+    }
+
+    /// Convert a time-since-midnight into an Eastern time datetime.
+    pub fn eastern(&self, time: NaiveTime) -> DateTime<Tz> {
+        self.midnight() + time.signed_duration_since(NaiveTime::MIN)
+    }
+
+    /// Convert a time-since-midnight into a UTC datetime.
+    pub fn utc(&self, time: NaiveTime) -> DateTime<Utc> {
+        self.eastern(time).with_timezone(&Utc)
+    }
+
+    /// Convert a time-since-midnight into nanoseconds since the Unix epoch.
+    pub fn utc_nanos(&self, time: NaiveTime) -> i64 {
+        self.utc(time)
+            .timestamp_nanos_opt()
+            .expect("Trading dates are within the representable range")
+    }
+
+    /// Convert raw nanoseconds since midnight 
+    /// into nanoseconds since the Unix epoch.
+    pub fn utc_nanos_from(&self, nanos: u64) -> i64 {
+        self.midnight()
+            .timestamp_nanos_opt()
+            .expect("Trading dates are within the representable range")
+            + nanos as i64
+    }
+
+    /// Nanoseconds since the Unix epoch at which a message was generated.
+    pub fn message_nanos(&self, metadata: &ItchMetadata) -> i64 {
+        self.utc_nanos_from(metadata.nanos)
+    }
+
+    /// Eastern time datetime at which a message was generated.
+    pub fn message_eastern(&self, metadata: &ItchMetadata) -> DateTime<Tz> {
+        self.eastern(metadata.timestamp())
+    }
+}
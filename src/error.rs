@@ -0,0 +1,30 @@
+
+/// Errors that can occur while receiving or decoding ITCH data.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+
+    /// Transport-level failure.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Transport framing (MoldUDP64, SoupBinTCP, length prefix) is invalid.
+    #[error("Malformed frame: {0}")]
+    Frame(&'static str),
+
+    /// A complete message could not be parsed.
+    #[error("Failed to parse ITCH message with tag {0:?}")]
+    Message(char),
+
+    /// Writing a Parquet file failed.
+    #[cfg(feature = "arrow")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+impl Error {
+
+    /// Describe a failure to parse the given message bytes.
+    pub fn message(bytes: &[u8]) -> Self {
+        Error::Message(bytes.first().map_or('\0', |b| *b as char))
+    }
+}
@@ -0,0 +1,260 @@
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayBuilder,
+    BooleanBuilder,
+    Decimal128Builder,
+    StringBuilder,
+    StringDictionaryBuilder,
+    Time64NanosecondBuilder,
+    UInt16Builder,
+    UInt32Builder,
+    UInt64Builder,
+};
+use arrow::datatypes::{ DataType, Int16Type, Int32Type, TimeUnit };
+use nsdq_util::{ Mpid, NaiveTime, Price, StockSymbol };
+
+use crate::msg::*;
+
+/// Field type that can be written to an Arrow column.
+pub(crate) trait Column {
+
+    type Builder: ArrayBuilder;
+
+    /// True if the column can hold nulls.
+    const NULLABLE: bool = false;
+
+    fn data_type() -> DataType;
+
+    fn builder() -> Self::Builder;
+
+    fn append(&self, builder: &mut Self::Builder);
+}
+
+macro_rules! primitive_column {
+    ($($ty:ty => $builder:ty, $data_type:expr;)*) => {$(
+        impl Column for $ty {
+            type Builder = $builder;
+            fn data_type() -> DataType { $data_type }
+            fn builder() -> Self::Builder { <$builder>::new() }
+            fn append(&self, builder: &mut Self::Builder) { 
+                builder.append_value(*self) 
+            }
+        }
+    )*}
+}
+
+primitive_column!{
+    u64 => UInt64Builder, DataType::UInt64;
+    u32 => UInt32Builder, DataType::UInt32;
+    u16 => UInt16Builder, DataType::UInt16;
+    bool => BooleanBuilder, DataType::Boolean;
+}
+
+/// Ternary flags are nullable booleans (`None` is "not available").
+impl Column for Option<bool> {
+    type Builder = BooleanBuilder;
+    const NULLABLE: bool = true;
+    fn data_type() -> DataType { DataType::Boolean }
+    fn builder() -> Self::Builder { BooleanBuilder::new() }
+    fn append(&self, builder: &mut Self::Builder) { builder.append_option(*self) }
+}
+
+impl Column for char {
+    type Builder = StringBuilder;
+    fn data_type() -> DataType { DataType::Utf8 }
+    fn builder() -> Self::Builder { StringBuilder::new() }
+    fn append(&self, builder: &mut Self::Builder) {
+        builder.append_value(self.encode_utf8(&mut [0; 4]))
+    }
+}
+
+/// Times within the day are nanoseconds since midnight.
+impl Column for NaiveTime {
+    type Builder = Time64NanosecondBuilder;
+    fn data_type() -> DataType { DataType::Time64(TimeUnit::Nanosecond) }
+    fn builder() -> Self::Builder { Time64NanosecondBuilder::new() }
+    fn append(&self, builder: &mut Self::Builder) {
+        builder.append_value(nanos_of_day(*self) as i64)
+    }
+}
+
+/// Prices keep their implied decimal places as an exact decimal.
+macro_rules! price_column {
+    ($($int:ty, $places:literal => $precision:literal;)*) => {$(
+        impl Column for Price<$int, $places> {
+            type Builder = Decimal128Builder;
+            fn data_type() -> DataType { DataType::Decimal128($precision, $places) }
+            fn builder() -> Self::Builder {
+                Decimal128Builder::new()
+                    .with_precision_and_scale($precision, $places as i8)
+                    .expect("Valid decimal precision")
+            }
+            fn append(&self, builder: &mut Self::Builder) {
+                builder.append_value(self.val() as i128)
+            }
+        }
+    )*}
+}
+
+price_column!{
+    u32, 4 => 10;
+    u64, 8 => 20;
+}
+
+/// Symbols and MPIDs repeat heavily, so they are dictionary-encoded.
+macro_rules! string_column {
+    ($($ty:ty),*) => {$(
+        impl Column for $ty {
+            type Builder = StringDictionaryBuilder<Int32Type>;
+            fn data_type() -> DataType {
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+            }
+            fn builder() -> Self::Builder { StringDictionaryBuilder::new() }
+            fn append(&self, builder: &mut Self::Builder) {
+                builder.append_value(self.to_str())
+            }
+        }
+    )*}
+}
+
+string_column!{ StockSymbol, Mpid }
+
+/// Protocol enums are dictionary-encoded by variant name.
+/// Variants are listed so that names are static strings 
+/// (rather than formatted for every row); the match is exhaustive, 
+/// so a variant missing here fails to compile.
+macro_rules! enum_column {
+    ($($ty:ident { $($variant:ident),* $(,)? }),* $(,)?) => {$(
+        impl Column for $ty {
+            type Builder = StringDictionaryBuilder<Int16Type>;
+            fn data_type() -> DataType {
+                DataType::Dictionary(Box::new(DataType::Int16), Box::new(DataType::Utf8))
+            }
+            fn builder() -> Self::Builder { StringDictionaryBuilder::new() }
+            fn append(&self, builder: &mut Self::Builder) {
+                builder.append_value(match self {
+                    $($ty::$variant => stringify!($variant),)*
+                })
+            }
+        }
+    )*}
+}
+
+enum_column!{
+    SystemEvent {
+        BeginMessages, BeginSystemHours, BeginMarketHours, EndMarketHours,
+        EndSystemHours, EndMessages,
+    },
+    MarketCategory {
+        NasdaqGlobalSelectMarket, NasdaqGlobalMarket, NasdaqCapitalMarket,
+        Nyse, NyseAmerican, NyseArca, BatsZExchange, InvestorsExchange,
+        NotAvailable,
+    },
+    FinancialStatus {
+        Deficient, Delinquent, Bankrupt, Suspended, DeficientBankrupt,
+        DeficientDelinquent, DelinquentBankrupt,
+        DeficientDelinquentBankrupt, CreateRedeemSuspended, Compliant,
+        NotAvailable,
+    },
+    IssueClassification {
+        AmericanDepositaryShare, Bond, CommonStock, DepositoryReceipt,
+        _144A, LimitedPartnership, Notes, OrdinaryShare, PreferredStock,
+        OtherSecurities, Right, SharesOfBeneficialInterest,
+        ConvertibleDebenture, Unit, UnitsBenifInt, Warrant,
+    },
+    IssueSubType {
+        PreferredTrustSecurities, AlphaIndexEtns, IndexBasedDerivative,
+        CommonShares, CommodityBasedTrustShares,
+        CommodityFuturesTrustShares, CommodityLinkedSecurities,
+        CommodityIndexTrustShares, CollateralizedMortgageObligation,
+        CurrencyTrustShares, CommodityCurrencyLinkedSecurities,
+        CurrencyWarrants, GlobalDepositaryShares,
+        EtfPortfolioDepositaryReceipt, EquityGoldShares,
+        EtnEquityIndexLinkedSecurities, NextSharesExchangeTradedManagedFund,
+        ExchangeTradedNotes, EquityUnits, Holdrs,
+        EtnFixedIncomeLinkedSecurities, EtnFuturesLinkedSecurities,
+        GlobalShares, EtfIndexFundShares, InterestRate, IndexWarrant,
+        IndexLinkedExchangeableNotes, CorporateBackedTrustSecurity,
+        ContingentLitigationRight, LimitedLiabilityCompany,
+        EquityBasedDerivative, ManagedFundShares,
+        EtnMultiFactorIndexLinkedSecurities, ManagedTrustSecurities,
+        NyRegistryShares, OpenEndedMutualFund, PrivatelyHeldSecurity,
+        PoisonPill, PartnershipUnits, ClosedEndFunds, RegS,
+        CommodityRedeemableCommodityLinkedSecurities,
+        EtnRedeemableFuturesLinkedSecurities, Reit,
+        CommodityRedeemableCurrencyLinkedSecurities, Seed, SpotRateClosing,
+        SpotRateIntraday, TrackingStock, TrustCertificates, TrustUnits,
+        Portal, ContingentValueRight, TrustIssuedReceipts,
+        WorldCurrencyOption, Trust, Other, NotApplicable,
+    },
+    Authenticity {
+        Production, Test,
+    },
+    LuldTier {
+        Tier1, Tier2, NotAvailable,
+    },
+    TradingState {
+        Halted, Paused, QuoteOnly, Trading,
+    },
+    TradingActionReason {
+        HaltNewsPending, HaltNewsDisseminated, SingleSecurityTradingPause,
+        ExtraordinaryMarketActivity, HaltEtf, TradingHalted,
+        HaltNonCompliance, HaltFilingsNotCurrent, HaltSecTradingSuspension,
+        HaltRegulatoryConcern, OperationsHalt, VolatilityPause,
+        VolatilityPauseStraddleCondition, MwcbHalt1, MwcbHalt2, MwcbHalt3,
+        MwcbHaltRemainInEffect, IpoIssue, CorporateAction,
+        QuotationNotAvailable, ReasonNotAvailable, NewsAndResumptionTimes,
+        SingleSecurityQuotationPause, QualificationsResolved,
+        FilingResolved, IssuerNewsNotForthcoming,
+        MaintenanceRequirementsMet, FilingsMet,
+        TradeHaltConcludedByRegulator, MwcbResumption, NewIssueAvailable,
+        IssueAvailable, IpoSecurityReleased, IpoPositioningWindowExtension,
+    },
+    RegShoAction {
+        NoPriceTest, PriceDrop, RemainInEffect,
+    },
+    MarketMakerMode {
+        Normal, Passive, Syndicate, PreSyndicate, Penalty,
+    },
+    MarketParticipantState {
+        Active, Excused, Withdrawn, Suspended, Deleted,
+    },
+    BreachedLevel {
+        _1, _2, _3,
+    },
+    IpoQuotationReleaseQualifier {
+        Anticipated, Canceled,
+    },
+    MarketCode {
+        Nasdaq, Bx, Psx,
+    },
+    HaltAction {
+        Halted, Trading,
+    },
+    Side {
+        Buy, Sell,
+    },
+    CrossType {
+        Opening, Closing, Halt,
+    },
+    ImbalanceDirection {
+        Buy, Sell, NoImbalance, Uncalculated, Paused,
+    },
+    ImbalanceCrossType {
+        Opening, Closing, Halt, ExtendedClose,
+    },
+    PriceVariation {
+        Zero, One, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten,
+        Twenty, ThirtyUp, Uncalculated,
+    },
+    InterestFlag {
+        BuyAvailable, SellAvailable, AnyAvailable, NoneAvailable,
+    },
+}
+
+/// Arrow field for a column type.
+pub(crate) fn field<T: Column>(name: &str) -> arrow::datatypes::FieldRef {
+    Arc::new(arrow::datatypes::Field::new(name, T::data_type(), T::NULLABLE))
+}
@@ -0,0 +1,406 @@
+
+//! Columnar export of ITCH messages.
+//!
+//! Each message kind gets its own Arrow schema: the common metadata 
+//! (`timestamp`, `stock_locate`, `tracking_number`) followed by the fields 
+//! of the message body, named as they are on the `kinds` structs.
+//!
+//! - Timestamps are nanoseconds: `Time64` since midnight, 
+//!   or UTC `Timestamp` when a `TradingDate` is supplied.
+//! - Prices are exact decimals (`Decimal128(10, 4)` and `Decimal128(20, 8)`).
+//! - Symbols, MPIDs and protocol enums are dictionary-encoded strings.
+
+mod column;
+mod parquet;
+
+pub use self::parquet::ParquetExporter;
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayBuilder,
+    ArrayRef,
+    Time64NanosecondBuilder,
+    TimestampNanosecondBuilder,
+    UInt16Builder,
+};
+use arrow::datatypes::{ DataType, Field, Schema, SchemaRef, TimeUnit };
+use arrow::record_batch::RecordBatch;
+
+use crate::{ ItchMessage, TradingDate };
+use crate::msg::ItchMetadata;
+use self::column::{ field, Column };
+
+/// Builders for the metadata columns shared by every kind.
+struct MetaColumns {
+    date: Option<TradingDate>,
+    local: Time64NanosecondBuilder,
+    utc: TimestampNanosecondBuilder,
+    stock_locate: UInt16Builder,
+    tracking_number: UInt16Builder,
+}
+
+impl MetaColumns {
+
+    fn new(date: Option<TradingDate>) -> Self {
+        Self {
+            date,
+            local: Time64NanosecondBuilder::new(),
+            utc: TimestampNanosecondBuilder::new().with_timezone("UTC"),
+            stock_locate: UInt16Builder::new(),
+            tracking_number: UInt16Builder::new(),
+        }
+    }
+
+    fn timestamp_type(date: Option<TradingDate>) -> DataType {
+        match date {
+            Some(_) => DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+            None => DataType::Time64(TimeUnit::Nanosecond),
+        }
+    }
+
+    fn fields(date: Option<TradingDate>) -> Vec<Arc<Field>> {
+        vec![
+            Arc::new(Field::new("timestamp", Self::timestamp_type(date), false)),
+            field::<u16>("stock_locate"),
+            field::<u16>("tracking_number"),
+        ]
+    }
+
+    fn append(&mut self, metadata: &ItchMetadata) {
+        match self.date {
+            Some(date) => self.utc.append_value(date.message_nanos(metadata)),
+            None => self.local.append_value(metadata.nanos as i64),
+        }
+        self.stock_locate.append_value(metadata.stock_locate);
+        self.tracking_number.append_value(metadata.tracking_number);
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        let timestamp = match self.date {
+            Some(_) => ArrayBuilder::finish(&mut self.utc),
+            None => ArrayBuilder::finish(&mut self.local),
+        };
+        vec![
+            timestamp,
+            ArrayBuilder::finish(&mut self.stock_locate),
+            ArrayBuilder::finish(&mut self.tracking_number),
+        ]
+    }
+}
+
+/// Generates a column builder table for each message kind.
+/// Kinds whose body is a single value (not a struct) name the column 
+/// in parentheses instead of braces.
+macro_rules! kind_tables {
+    (
+        $($kind:ident $({ $($field:ident: $ty:ty),* $(,)? })? 
+            $(( $single:ident: $sty:ty ))?
+        ),* $(,)?
+    ) => {
+
+        #[allow(non_snake_case)]
+        mod tables {
+
+            use super::*;
+
+            $(
+                pub(super) struct $kind {
+                    meta: MetaColumns,
+                    rows: usize,
+                    $($($field: <$ty as Column>::Builder,)*)?
+                    $($single: <$sty as Column>::Builder,)?
+                }
+
+                impl $kind {
+
+                    pub(super) fn new(date: Option<TradingDate>) -> Self {
+                        Self {
+                            meta: MetaColumns::new(date),
+                            rows: 0,
+                            $($($field: <$ty as Column>::builder(),)*)?
+                            $($single: <$sty as Column>::builder(),)?
+                        }
+                    }
+
+                    pub(super) fn schema(date: Option<TradingDate>) -> Schema {
+                        let mut fields = MetaColumns::fields(date);
+                        $($(fields.push(field::<$ty>(stringify!($field)));)*)?
+                        $(fields.push(field::<$sty>(stringify!($single)));)?
+                        Schema::new(fields)
+                    }
+
+                    pub(super) fn append(
+                        &mut self, 
+                        metadata: &ItchMetadata, 
+                        body: &crate::msg::$kind
+                    ) {
+                        self.meta.append(metadata);
+                        $($(body.$field.append(&mut self.$field);)*)?
+                        $(body.append(&mut self.$single);)?
+                        self.rows += 1;
+                    }
+
+                    pub(super) fn rows(&self) -> usize { self.rows }
+
+                    pub(super) fn finish(&mut self, schema: SchemaRef) -> RecordBatch {
+                        let mut columns = self.meta.finish();
+                        $($(columns.push(ArrayBuilder::finish(&mut self.$field));)*)?
+                        $(columns.push(ArrayBuilder::finish(&mut self.$single));)?
+                        self.rows = 0;
+                        RecordBatch::try_new(schema, columns)
+                            .expect("Columns match the generated schema")
+                    }
+                }
+            )*
+
+            pub(super) struct Tables {
+                $(pub(super) $kind: ($kind, SchemaRef),)*
+            }
+
+            impl Tables {
+
+                pub(super) fn new(date: Option<TradingDate>) -> Self {
+                    Self {$(
+                        $kind: ($kind::new(date), Arc::new($kind::schema(date))),
+                    )*}
+                }
+
+                /// Append a message, returning the row count of its table.
+                pub(super) fn push(&mut self, msg: &ItchMessage) -> usize {
+                    match msg {$(
+                        ItchMessage::$kind { metadata, body } => {
+                            self.$kind.0.append(metadata, body);
+                            self.$kind.0.rows()
+                        },
+                    )*}
+                }
+
+                pub(super) fn rows(&self, kind: &str) -> Option<usize> {
+                    match kind {
+                        $(stringify!($kind) => Some(self.$kind.0.rows()),)*
+                        _ => None,
+                    }
+                }
+
+                pub(super) fn schema(&self, kind: &str) -> Option<SchemaRef> {
+                    match kind {
+                        $(stringify!($kind) => Some(self.$kind.1.clone()),)*
+                        _ => None,
+                    }
+                }
+
+                pub(super) fn finish(&mut self, kind: &str) -> Option<RecordBatch> {
+                    match kind {
+                        $(stringify!($kind) => {
+                            let (table, schema) = &mut self.$kind;
+                            Some(table.finish(schema.clone()))
+                        },)*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    }
+}
+
+kind_tables!{
+    SystemEvent (event: crate::msg::SystemEvent),
+    StockDirectory {
+        stock: nsdq_util::StockSymbol,
+        market_category: crate::msg::MarketCategory,
+        financial_status: crate::msg::FinancialStatus,
+        round_lot_size: u32,
+        round_lots_only: bool,
+        class: crate::msg::IssueClassification,
+        subtype: crate::msg::IssueSubType,
+        authenticity: crate::msg::Authenticity,
+        short_sale_threshold: Option<bool>,
+        ipo_flag: Option<bool>,
+        luld_tier: crate::msg::LuldTier,
+        etp_flag: Option<bool>,
+        etp_leverage_factor: u32,
+        inverse: bool,
+    },
+    TradingAction {
+        stock: nsdq_util::StockSymbol,
+        state: crate::msg::TradingState,
+        reserved: char,
+        reason: crate::msg::TradingActionReason,
+    },
+    RegShoRestriction {
+        stock: nsdq_util::StockSymbol,
+        action: crate::msg::RegShoAction,
+    },
+    MarketParticipantPosition {
+        mpid: nsdq_util::Mpid,
+        stock: nsdq_util::StockSymbol,
+        is_primary: bool,
+        mode: crate::msg::MarketMakerMode,
+        state: crate::msg::MarketParticipantState,
+    },
+    MwcbDeclineLevel {
+        level_1: nsdq_util::Price<u64, 8>,
+        level_2: nsdq_util::Price<u64, 8>,
+        level_3: nsdq_util::Price<u64, 8>,
+    },
+    MwcbStatus {
+        level: crate::msg::BreachedLevel,
+    },
+    QuotingPeriodUpdate {
+        release_time: nsdq_util::NaiveTime,
+        qualifier: crate::msg::IpoQuotationReleaseQualifier,
+        ipo_price: nsdq_util::Price<u32, 4>,
+    },
+    LuldAuctionCollar {
+        stock: nsdq_util::StockSymbol,
+        reference_price: nsdq_util::Price<u32, 4>,
+        upper_price: nsdq_util::Price<u32, 4>,
+        lower_price: nsdq_util::Price<u32, 4>,
+        extension: u32,
+    },
+    OperationalHalt {
+        stock: nsdq_util::StockSymbol,
+        market: crate::msg::MarketCode,
+        action: crate::msg::HaltAction,
+    },
+    OrderAdded {
+        order_ref_num: u64,
+        side: crate::msg::Side,
+        quantity: u32,
+        stock: nsdq_util::StockSymbol,
+        price: nsdq_util::Price<u32, 4>,
+    },
+    OrderAddedWithMpid {
+        order_ref_num: u64,
+        side: crate::msg::Side,
+        quantity: u32,
+        stock: nsdq_util::StockSymbol,
+        price: nsdq_util::Price<u32, 4>,
+        mpid: nsdq_util::Mpid,
+    },
+    OrderExecuted {
+        order_ref_num: u64,
+        quantity: u32,
+        match_number: u64,
+    },
+    OrderExecutedWithPrice {
+        order_ref_num: u64,
+        quantity: u32,
+        match_number: u64,
+        printable: bool,
+        price: nsdq_util::Price<u32, 4>,
+    },
+    OrderCanceled {
+        order_ref_num: u64,
+        quantity: u32,
+    },
+    OrderDeleted {
+        order_ref_num: u64,
+    },
+    OrderReplaced {
+        old_ref_num: u64,
+        new_ref_num: u64,
+        quantity: u32,
+        price: nsdq_util::Price<u32, 4>,
+    },
+    MatchTrade {
+        order_ref_num: u64,
+        quantity: u32,
+        stock: nsdq_util::StockSymbol,
+        price: nsdq_util::Price<u32, 4>,
+        match_number: u64,
+    },
+    CrossTrade {
+        order_ref_num: u64,
+        quantity: u32,
+        stock: nsdq_util::StockSymbol,
+        price: nsdq_util::Price<u32, 4>,
+        match_number: u64,
+        cross_type: crate::msg::CrossType,
+    },
+    BrokenTrade {
+        match_number: u64,
+    },
+    NetOrderImbalance {
+        paired_shares: u64,
+        imbalance_shares: u64,
+        imbalance_direction: crate::msg::ImbalanceDirection,
+        stock: nsdq_util::StockSymbol,
+        far_price: nsdq_util::Price<u32, 4>,
+        near_price: nsdq_util::Price<u32, 4>,
+        ref_price: nsdq_util::Price<u32, 4>,
+        cross_type: crate::msg::ImbalanceCrossType,
+        price_variation: crate::msg::PriceVariation,
+    },
+    RetailPriceImprovement {
+        stock: nsdq_util::StockSymbol,
+        interest_flag: crate::msg::InterestFlag,
+    },
+    DirectListingWithCapitalRaise {
+        stock: nsdq_util::StockSymbol,
+        eligibility: bool,
+        min_price: nsdq_util::Price<u32, 4>,
+        max_price: nsdq_util::Price<u32, 4>,
+        near_exec_price: nsdq_util::Price<u32, 4>,
+        near_exec_time: nsdq_util::NaiveTime,
+        lower_collar: nsdq_util::Price<u32, 4>,
+        upper_collar: nsdq_util::Price<u32, 4>,
+    },
+}
+
+/// Accumulates messages into per-kind Arrow column builders.
+pub struct ArrowConverter {
+    tables: tables::Tables,
+}
+
+impl ArrowConverter {
+
+    /// Timestamps are written as `Time64` nanoseconds since midnight.
+    pub fn new() -> Self {
+        Self { tables: tables::Tables::new(None) }
+    }
+
+    /// Timestamps are written as UTC `Timestamp` nanoseconds 
+    /// on the given trading date.
+    pub fn with_date(date: TradingDate) -> Self {
+        Self { tables: tables::Tables::new(Some(date)) }
+    }
+
+    /// Append a message to the table for its kind.
+    /// Returns the number of rows now buffered for that kind.
+    pub fn push(&mut self, msg: &ItchMessage) -> usize {
+        self.tables.push(msg)
+    }
+
+    /// Number of rows buffered for a kind (`None` if the name is unknown).
+    pub fn rows(&self, kind: &str) -> Option<usize> {
+        self.tables.rows(kind)
+    }
+
+    /// Arrow schema used for a message kind (e.g. `"OrderAdded"`).
+    pub fn schema(&self, kind: &str) -> Option<SchemaRef> {
+        self.tables.schema(kind)
+    }
+
+    /// Take the buffered rows of one kind as a batch, 
+    /// leaving its builders empty.
+    pub fn finish_kind(&mut self, kind: &str) -> Option<RecordBatch> {
+        self.tables.finish(kind)
+    }
+
+    /// Take a batch for every kind that has buffered rows.
+    pub fn finish(&mut self) -> Vec<(&'static str, RecordBatch)> {
+        let kinds: Vec<_> = ItchMessage::KINDS.iter()
+            .filter(|kind| self.rows(kind).is_some_and(|rows| rows > 0))
+            .collect();
+
+        kinds.into_iter()
+            .filter_map(|&kind| Some((kind, self.finish_kind(kind)?)))
+            .collect()
+    }
+}
+
+impl Default for ArrowConverter {
+    fn default() -> Self { Self::new() }
+}
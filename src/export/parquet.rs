@@ -0,0 +1,116 @@
+
+use std::collections::{ hash_map::Entry, HashMap };
+use std::fs::{ self, File };
+use std::path::{ Path, PathBuf };
+
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{ Compression, ZstdLevel };
+use parquet::file::properties::WriterProperties;
+
+use crate::{ Error, ItchMessage, TradingDate };
+use super::ArrowConverter;
+
+/// Rows buffered per kind before a row group is written.
+const DEFAULT_BATCH_ROWS: usize = 64 * 1024;
+
+/// Writes a day of messages to one Parquet file per message kind
+/// (`<dir>/<Kind>.parquet`), created when the first message of that kind 
+/// arrives.
+pub struct ParquetExporter {
+    dir: PathBuf,
+    batch_rows: usize,
+    properties: WriterProperties,
+    converter: ArrowConverter,
+    writers: HashMap<&'static str, ArrowWriter<File>>,
+}
+
+impl ParquetExporter {
+
+    /// Export into `dir` (created if missing), with `Time64` timestamps.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::with_converter(dir, ArrowConverter::new())
+    }
+
+    /// Export into `dir` with UTC timestamps on the given trading date.
+    pub fn with_date(dir: impl AsRef<Path>, date: TradingDate) -> Result<Self, Error> {
+        Self::with_converter(dir, ArrowConverter::with_date(date))
+    }
+
+    fn with_converter(dir: impl AsRef<Path>, converter: ArrowConverter) -> Result<Self, Error> {
+
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+
+        Ok(Self {
+            dir,
+            batch_rows: DEFAULT_BATCH_ROWS,
+            properties,
+            converter,
+            writers: HashMap::new(),
+        })
+    }
+
+    /// Number of rows buffered per kind before they are written out.
+    pub fn batch_rows(mut self, rows: usize) -> Self {
+        self.batch_rows = rows.max(1);
+        self
+    }
+
+    /// Override the Parquet writer properties (compression, statistics).
+    pub fn properties(mut self, properties: WriterProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Buffer a message, writing out its kind once a batch is full.
+    pub fn push(&mut self, msg: &ItchMessage) -> Result<(), Error> {
+        if self.converter.push(msg) >= self.batch_rows {
+            self.flush_kind(msg.kind())?;
+        }
+        Ok(())
+    }
+
+    fn flush_kind(&mut self, kind: &'static str) -> Result<(), Error> {
+
+        let Some(batch) = self.converter.finish_kind(kind) else { return Ok(()) };
+        if batch.num_rows() == 0 { return Ok(()) }
+
+        let writer = match self.writers.entry(kind) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let file = File::create(self.dir.join(format!("{kind}.parquet")))?;
+                let writer = ArrowWriter::try_new(
+                    file, 
+                    batch.schema(), 
+                    Some(self.properties.clone())
+                )?;
+                e.insert(writer)
+            }
+        };
+
+        Ok(writer.write(&batch)?)
+    }
+
+    /// Write out remaining rows and close every file.
+    /// Returns the paths written, in protocol order.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, Error> {
+
+        for kind in ItchMessage::KINDS {
+            self.flush_kind(kind)?;
+        }
+
+        let mut paths = Vec::new();
+        for kind in ItchMessage::KINDS {
+            if let Some(writer) = self.writers.remove(kind) {
+                writer.close()?;
+                paths.push(self.dir.join(format!("{kind}.parquet")));
+            }
+        }
+
+        Ok(paths)
+    }
+}
@@ -0,0 +1,221 @@
+
+use std::io;
+use std::mem;
+use std::net::UdpSocket;
+use std::ops::ControlFlow;
+use std::os::fd::AsRawFd;
+
+use crate::{ Error, ItchMessage };
+use crate::frame::{ MoldPacket, Sequencer };
+use super::{ Received, sys };
+
+/// Running totals kept by a `BatchReceiver`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchStats {
+
+    /// `recvmmsg` calls that returned data.
+    pub batches: u64,
+    /// Datagrams received.
+    pub datagrams: u64,
+    /// Messages parsed (excluding duplicates and parse failures).
+    pub messages: u64,
+    /// Datagrams dropped by the kernel because the socket buffer was full.
+    pub kernel_drops: u64,
+    /// Datagrams larger than the buffer they were received into.
+    /// These are discarded, so their messages show up as a sequence gap.
+    pub truncated: u64,
+}
+
+/// Receives many datagrams per system call with `recvmmsg`,
+/// into a preallocated ring of fixed-size buffers.
+///
+/// Suited to the bursts at the open and close, when one `recv_from` 
+/// per datagram cannot keep up with the feed.
+pub struct BatchReceiver {
+    socket: UdpSocket,
+    buffer_len: usize,
+    buffers: Vec<u8>,
+    lens: Vec<usize>,
+    truncated: Vec<bool>,
+    controls: Vec<sys::Control>,
+    iovecs: Vec<libc::iovec>,
+    headers: Vec<libc::mmsghdr>,
+    cmsgs: Vec<sys::ControlBuf>,
+    filled: usize,
+    sequencer: Sequencer,
+    stats: BatchStats,
+}
+
+// SAFETY: The raw pointers in `iovecs` and `headers` only point into 
+// buffers owned by the receiver, and are rebuilt before every receive.
+unsafe impl Send for BatchReceiver {}
+
+impl BatchReceiver {
+
+    /// Default number of datagrams received per call.
+    pub const DEFAULT_BATCH: usize = 64;
+    /// Default buffer size per datagram (larger than a standard MTU).
+    pub const DEFAULT_BUFFER_LEN: usize = 2048;
+
+    /// Wrap a configured socket (see `FeedConfig::socket`),
+    /// receiving up to `batch` datagrams of up to `buffer_len` bytes per call.
+    /// Enables the kernel drop counter on the socket.
+    pub fn new(socket: UdpSocket, batch: usize, buffer_len: usize) -> io::Result<Self> {
+
+        sys::enable_drop_counter(socket.as_raw_fd())?;
+        let batch = batch.max(1);
+
+        Ok(Self {
+            socket,
+            buffer_len,
+            buffers: vec![0u8; batch * buffer_len],
+            lens: vec![0; batch],
+            truncated: vec![false; batch],
+            controls: vec![sys::Control::default(); batch],
+            // SAFETY: Zeroed `iovec` and `mmsghdr` are valid (null pointers).
+            iovecs: vec![unsafe { mem::zeroed() }; batch],
+            headers: vec![unsafe { mem::zeroed() }; batch],
+            cmsgs: vec![sys::ControlBuf::new(); batch],
+            filled: 0,
+            sequencer: Sequencer::new(),
+            stats: BatchStats::default(),
+        })
+    }
+
+    /// Wrap a socket with the default batch and buffer sizes.
+    pub fn with_defaults(socket: UdpSocket) -> io::Result<Self> {
+        Self::new(socket, Self::DEFAULT_BATCH, Self::DEFAULT_BUFFER_LEN)
+    }
+
+    /// The underlying socket.
+    pub fn socket(&self) -> &UdpSocket { &self.socket }
+
+    /// Sequence tracking (gaps and duplicates) for received packets.
+    pub fn sequencer(&self) -> &Sequencer { &self.sequencer }
+
+    /// Counters for everything received so far.
+    pub fn stats(&self) -> BatchStats { self.stats }
+
+    /// Block until at least one datagram arrives, then take as many as are
+    /// available (up to the batch size). Returns the number received.
+    pub fn recv_batch(&mut self) -> io::Result<usize> {
+
+        let batch = self.lens.len();
+        for i in 0..batch {
+
+            let buf = &mut self.buffers[i * self.buffer_len..(i + 1) * self.buffer_len];
+            self.iovecs[i] = libc::iovec {
+                iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+                iov_len: buf.len(),
+            };
+
+            let hdr = &mut self.headers[i];
+            hdr.msg_len = 0;
+            hdr.msg_hdr.msg_name = std::ptr::null_mut();
+            hdr.msg_hdr.msg_namelen = 0;
+            hdr.msg_hdr.msg_iov = &mut self.iovecs[i];
+            hdr.msg_hdr.msg_iovlen = 1;
+            hdr.msg_hdr.msg_control = self.cmsgs[i].0.as_mut_ptr() as *mut libc::c_void;
+            hdr.msg_hdr.msg_controllen = self.cmsgs[i].0.len() as _;
+            hdr.msg_hdr.msg_flags = 0;
+        }
+
+        // SAFETY: Every header points into buffers owned by `self`.
+        let received = unsafe {
+            libc::recvmmsg(
+                self.socket.as_raw_fd(),
+                self.headers.as_mut_ptr(),
+                batch as _,
+                libc::MSG_WAITFORONE,
+                std::ptr::null_mut(),
+            )
+        };
+        if received < 0 {
+            self.filled = 0;
+            return Err(io::Error::last_os_error())
+        }
+
+        let received = received as usize;
+        for i in 0..received {
+
+            let hdr = &self.headers[i];
+            self.lens[i] = hdr.msg_len as usize;
+            self.controls[i] = sys::control_messages(&hdr.msg_hdr);
+
+            self.truncated[i] = hdr.msg_hdr.msg_flags & libc::MSG_TRUNC != 0;
+            if self.truncated[i] {
+                self.stats.truncated += 1;
+            }
+            if let Some(drops) = self.controls[i].drops {
+                self.stats.kernel_drops = self.stats.kernel_drops.max(drops as u64);
+            }
+        }
+
+        self.filled = received;
+        self.stats.batches += 1;
+        self.stats.datagrams += received as u64;
+        Ok(received)
+    }
+
+    /// Payloads of the datagrams from the last `recv_batch`.
+    pub fn datagrams(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.filled).map(|i| {
+            let start = i * self.buffer_len;
+            &self.buffers[start..start + self.lens[i].min(self.buffer_len)]
+        })
+    }
+
+    /// Parse every message in the last batch, in order, 
+    /// passing new (non-duplicate) messages to `handler`.
+    /// Truncated datagrams are reported as an error and otherwise skipped,
+    /// so the sequencer counts their messages as missed.
+    pub fn parse_batch<F>(&mut self, handler: &mut F) -> ControlFlow<()>
+    where F: FnMut(Result<Received, Error>) -> ControlFlow<()> {
+
+        for i in 0..self.filled {
+
+            let start = i * self.buffer_len;
+            let bytes = &self.buffers[start..start + self.lens[i].min(self.buffer_len)];
+            let received = self.controls[i].received;
+
+            if self.truncated[i] {
+                handler(Err(Error::Frame("Datagram larger than the receive buffer")))?;
+                continue
+            }
+
+            let Some(packet) = MoldPacket::parse(bytes) else {
+                handler(Err(Error::Frame("Datagram shorter than MoldUDP64 header")))?;
+                continue
+            };
+
+            if packet.is_end_of_session() { return ControlFlow::Break(()) }
+
+            let skip = self.sequencer.accept(&packet);
+            for (j, bytes) in packet.messages().enumerate().skip(skip) {
+                let parsed = ItchMessage::from_bytes(bytes).map(|message| Received {
+                    message,
+                    sequence: packet.sequence + j as u64,
+                    received,
+                });
+                if parsed.is_ok() {
+                    self.stats.messages += 1;
+                }
+                handler(parsed)?;
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    /// Receive and parse batches until the end of the session
+    /// or until `handler` returns `ControlFlow::Break`.
+    /// Socket errors (including read timeouts) end the loop.
+    pub fn run<F>(&mut self, mut handler: F) -> io::Result<()> 
+    where F: FnMut(Result<Received, Error>) -> ControlFlow<()> {
+
+        loop {
+            self.recv_batch()?;
+            if self.parse_batch(&mut handler).is_break() { return Ok(()) }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+
+//! Multicast receiver setup for the TotalView-ITCH feed (Linux only).
+
+/// Raw socket calls that are not exposed by `std` or `socket2`.
+mod sys;
+/// Batched receive path using `recvmmsg`.
+pub mod batch;
+
+pub use batch::{ BatchReceiver, BatchStats };
+
+use std::io;
+use std::net::{ Ipv4Addr, SocketAddrV4, UdpSocket };
+use std::ops::ControlFlow;
+use std::os::fd::AsRawFd;
+use std::time::{ Duration, SystemTime };
+
+use socket2::{ Domain, Protocol, Socket, Type };
+
+use crate::{ Error, ItchMessage };
+use crate::frame::{ MoldPacket, Sequencer };
+
+/// Settings for a multicast socket joined to an ITCH feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedConfig {
+
+    /// Multicast group carrying the feed.
+    pub group: Ipv4Addr,
+    /// UDP port of the feed.
+    pub port: u16,
+    /// Address of the local interface to join on 
+    /// (`UNSPECIFIED` lets the kernel choose).
+    pub interface: Ipv4Addr,
+    /// Source address for source-specific multicast (SSM), if used.
+    pub source: Option<Ipv4Addr>,
+    /// Requested `SO_RCVBUF` size in bytes. 
+    /// NOTE: The kernel caps this at `net.core.rmem_max`.
+    pub recv_buffer: usize,
+    /// Allow other sockets to bind the same group and port (`SO_REUSEADDR`).
+    pub reuse_address: bool,
+    /// Ask the kernel to timestamp each datagram on arrival (`SO_TIMESTAMPNS`).
+    pub timestamps: bool,
+    /// Timeout for each receive call, or `None` to block indefinitely.
+    pub read_timeout: Option<Duration>,
+}
+
+impl FeedConfig {
+
+    /// Configuration for the given group and port, with defaults suited to
+    /// a high-rate feed (64 MiB receive buffer, address reuse, timestamps).
+    pub fn new(group: Ipv4Addr, port: u16) -> Self {
+        Self {
+            group,
+            port,
+            interface: Ipv4Addr::UNSPECIFIED,
+            source: None,
+            recv_buffer: 64 * 1024 * 1024,
+            reuse_address: true,
+            timestamps: true,
+            read_timeout: None,
+        }
+    }
+
+    /// Create, configure, bind and join the socket.
+    /// The result can also be handed to `tokio::net::UdpSocket::from_std`
+    /// (after `set_nonblocking(true)`) for use with an `ItchStream`.
+    pub fn socket(&self) -> io::Result<UdpSocket> {
+
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+        socket.set_reuse_address(self.reuse_address)?;
+        socket.set_recv_buffer_size(self.recv_buffer)?;
+        socket.set_read_timeout(self.read_timeout)?;
+
+        // Binding to the group (rather than INADDR_ANY) keeps out traffic 
+        // for other groups that happen to use the same port.
+        socket.bind(&SocketAddrV4::new(self.group, self.port).into())?;
+
+        match self.source {
+            Some(source) => socket.join_ssm_v4(&source, &self.group, &self.interface)?,
+            None => socket.join_multicast_v4(&self.group, &self.interface)?,
+        }
+
+        if self.timestamps {
+            sys::enable_timestamps(socket.as_raw_fd())?;
+        }
+
+        Ok(socket.into())
+    }
+
+    /// Create the socket and wrap it in a `FeedReceiver`.
+    pub fn bind(&self) -> io::Result<FeedReceiver> {
+        Ok(FeedReceiver::new(self.socket()?))
+    }
+}
+
+/// Datagram as delivered by the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Datagram<'a> {
+
+    /// Payload of the datagram.
+    pub bytes: &'a [u8],
+    /// Time the kernel received the datagram, if timestamps are enabled.
+    pub received: Option<SystemTime>,
+}
+
+/// Message delivered by the receive loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Received {
+
+    /// The parsed message.
+    pub message: ItchMessage,
+    /// MoldUDP64 sequence number of the message.
+    pub sequence: u64,
+    /// Time the kernel received the datagram, if timestamps are enabled.
+    pub received: Option<SystemTime>,
+}
+
+/// Blocking receiver for MoldUDP64 datagrams carrying ITCH messages.
+#[derive(Debug)]
+pub struct FeedReceiver {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+    sequencer: Sequencer,
+}
+
+impl FeedReceiver {
+
+    /// Wrap an already configured socket.
+    pub fn new(socket: UdpSocket) -> Self {
+        Self { socket, buf: vec![0u8; 65_535], sequencer: Sequencer::new() }
+    }
+
+    /// The underlying socket.
+    pub fn socket(&self) -> &UdpSocket { &self.socket }
+
+    /// Sequence tracking (gaps and duplicates) for received packets.
+    pub fn sequencer(&self) -> &Sequencer { &self.sequencer }
+
+    /// Receive a single datagram, along with its kernel timestamp.
+    pub fn recv(&mut self) -> io::Result<Datagram<'_>> {
+
+        let (len, received) = sys::recv_timestamped(
+            self.socket.as_raw_fd(), 
+            &mut self.buf
+        )?;
+
+        Ok(Datagram { bytes: &self.buf[..len], received })
+    }
+
+    /// Receive and parse datagrams until the end of the session, 
+    /// passing every new (non-duplicate) message to `handler`.
+    /// Stops early if `handler` returns `ControlFlow::Break`.
+    ///
+    /// Malformed messages are passed to `handler` as errors; 
+    /// socket errors (including read timeouts) end the loop.
+    pub fn run<F>(&mut self, mut handler: F) -> io::Result<()> 
+    where F: FnMut(Result<Received, Error>) -> ControlFlow<()> {
+
+        loop {
+
+            let (len, received) = sys::recv_timestamped(
+                self.socket.as_raw_fd(), 
+                &mut self.buf
+            )?;
+
+            let Some(packet) = MoldPacket::parse(&self.buf[..len]) else {
+                let err = Error::Frame("Datagram shorter than MoldUDP64 header");
+                if handler(Err(err)).is_break() { return Ok(()) }
+                continue
+            };
+
+            if packet.is_end_of_session() { return Ok(()) }
+
+            let skip = self.sequencer.accept(&packet);
+            let messages = packet.messages().enumerate().skip(skip);
+
+            for (i, bytes) in messages {
+                let result = ItchMessage::from_bytes(bytes).map(|message| Received {
+                    message,
+                    sequence: packet.sequence + i as u64,
+                    received,
+                });
+                if handler(result).is_break() { return Ok(()) }
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+
+use std::io;
+use std::mem;
+use std::os::fd::RawFd;
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
+/// Turn on `SO_TIMESTAMPNS` for the socket.
+pub(crate) fn enable_timestamps(fd: RawFd) -> io::Result<()> {
+    set_int_option(fd, libc::SO_TIMESTAMPNS, 1)
+}
+
+/// Turn on `SO_RXQ_OVFL` (kernel drop counter) for the socket.
+pub(crate) fn enable_drop_counter(fd: RawFd) -> io::Result<()> {
+    set_int_option(fd, libc::SO_RXQ_OVFL, 1)
+}
+
+pub(crate) fn set_int_option(fd: RawFd, option: libc::c_int, value: libc::c_int) -> io::Result<()> {
+
+    let ret = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            option,
+            &value as *const libc::c_int as *const libc::c_void,
+            mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+
+    if ret == 0 { Ok(()) } else { Err(io::Error::last_os_error()) }
+}
+
+/// Control message buffer, aligned for `cmsghdr`.
+/// Large enough for a timestamp and a drop counter together.
+#[derive(Clone, Copy)]
+#[repr(C, align(8))]
+pub(crate) struct ControlBuf(pub [u8; 128]);
+
+impl ControlBuf {
+    pub(crate) fn new() -> Self { Self([0; 128]) }
+}
+
+/// Ancillary data attached to a received datagram.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Control {
+    /// `SCM_TIMESTAMPNS`: time the kernel received the datagram.
+    pub received: Option<SystemTime>,
+    /// `SO_RXQ_OVFL`: datagrams dropped by the socket so far.
+    pub drops: Option<u32>,
+}
+
+/// Receive one datagram into `buf` with `recvmsg`,
+/// returning its length and kernel timestamp (if enabled).
+pub(crate) fn recv_timestamped(
+    fd: RawFd, 
+    buf: &mut [u8],
+) -> io::Result<(usize, Option<SystemTime>)> {
+
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let mut control = ControlBuf::new();
+
+    // SAFETY: Zeroed `msghdr` is valid; pointers outlive the call.
+    let mut hdr: libc::msghdr = unsafe { mem::zeroed() };
+    hdr.msg_iov = &mut iov;
+    hdr.msg_iovlen = 1;
+    hdr.msg_control = control.0.as_mut_ptr() as *mut libc::c_void;
+    hdr.msg_controllen = control.0.len() as _;
+
+    let len = unsafe { libc::recvmsg(fd, &mut hdr, 0) };
+    if len < 0 {
+        return Err(io::Error::last_os_error())
+    }
+
+    Ok((len as usize, control_messages(&hdr).received))
+}
+
+/// Extract the control messages from a received header.
+pub(crate) fn control_messages(hdr: &libc::msghdr) -> Control {
+
+    let mut control = Control::default();
+
+    // SAFETY: `hdr` was filled by the kernel, so the cmsg chain is valid.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(hdr);
+        while !cmsg.is_null() {
+            let data = libc::CMSG_DATA(cmsg);
+            match ((*cmsg).cmsg_level, (*cmsg).cmsg_type) {
+                (libc::SOL_SOCKET, libc::SCM_TIMESTAMPNS) => {
+                    let ts = std::ptr::read_unaligned(data as *const libc::timespec);
+                    let since = Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32);
+                    control.received = Some(UNIX_EPOCH + since);
+                },
+                (libc::SOL_SOCKET, libc::SO_RXQ_OVFL) => {
+                    control.drops = Some(std::ptr::read_unaligned(data as *const u32));
+                },
+                _ => {},
+            }
+            cmsg = libc::CMSG_NXTHDR(hdr, cmsg);
+        }
+    }
+
+    control
+}
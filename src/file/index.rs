@@ -0,0 +1,145 @@
+
+use std::fs;
+use std::io;
+use std::path::{ Path, PathBuf };
+
+use crate::frame::split_length_prefixed;
+use super::message_nanos;
+
+const MAGIC: &[u8; 8] = b"LITCHTIX";
+const VERSION: u32 = 2;
+const HEADER_LEN: usize = 40;
+/// Bytes at each end of the file covered by the fingerprint.
+const FINGERPRINT_LEN: usize = 4096;
+
+/// Messages between index entries.
+pub const DEFAULT_STRIDE: usize = 4096;
+
+/// Sparse timestamp index over a length-prefixed file: 
+/// the byte offset and timestamp of every `stride`-th message.
+///
+/// Cached as `<file>.tidx`: magic `LITCHTIX`, version `u32`, stride `u32`, 
+/// file length `u64`, fingerprint `u64`, entry count `u64`, 
+/// then (nanos `u64`, offset `u64`) entries, all little-endian.
+/// The fingerprint is a hash of the first and last 4 KiB of the file, 
+/// so that a cache is not reused for a different file of the same length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeIndex {
+    stride: usize,
+    file_len: u64,
+    fingerprint: u64,
+    entries: Vec<(u64, u64)>,
+}
+
+/// FNV-1a hash of the first and last blocks of a file.
+fn fingerprint(bytes: &[u8]) -> u64 {
+
+    let head = &bytes[..bytes.len().min(FINGERPRINT_LEN)];
+    let tail = &bytes[bytes.len().saturating_sub(FINGERPRINT_LEN)..];
+    head.iter().chain(tail).fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl TimeIndex {
+
+    /// Index the messages in `bytes` with an entry every `stride` messages.
+    pub fn build(bytes: &[u8], stride: usize) -> Self {
+
+        let stride = stride.max(1);
+        let mut entries = Vec::new();
+        let mut rest = bytes;
+        let mut count = 0;
+
+        while let Some((msg, next)) = split_length_prefixed(rest) {
+            if count % stride == 0 {
+                let offset = (bytes.len() - rest.len()) as u64;
+                entries.push((message_nanos(msg).unwrap_or_default(), offset));
+            }
+            count += 1;
+            rest = next;
+        }
+
+        Self { stride, file_len: bytes.len() as u64, fingerprint: fingerprint(bytes), entries }
+    }
+
+    /// Messages between entries.
+    pub fn stride(&self) -> usize { self.stride }
+
+    /// Length of the file the index was built from.
+    pub fn file_len(&self) -> u64 { self.file_len }
+
+    /// Fingerprint of the file the index was built from.
+    pub fn fingerprint(&self) -> u64 { self.fingerprint }
+
+    /// True if the index was built from these file contents 
+    /// (same length and fingerprint).
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        self.file_len == bytes.len() as u64 && self.fingerprint == fingerprint(bytes)
+    }
+
+    /// Indexed (timestamp, byte offset) pairs, in file order.
+    pub fn entries(&self) -> &[(u64, u64)] { &self.entries }
+
+    /// Offset of the last indexed message generated before `nanos`,
+    /// from which a scan will reach the first message at or after it.
+    pub fn offset_before(&self, nanos: u64) -> u64 {
+        let i = self.entries.partition_point(|&(t, _)| t < nanos);
+        i.checked_sub(1).map_or(0, |i| self.entries[i].1)
+    }
+
+    /// Write the index to a cache file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 16 * self.entries.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.stride as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.file_len.to_le_bytes());
+        bytes.extend_from_slice(&self.fingerprint.to_le_bytes());
+        bytes.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        for (nanos, offset) in &self.entries {
+            bytes.extend_from_slice(&nanos.to_le_bytes());
+            bytes.extend_from_slice(&offset.to_le_bytes());
+        }
+
+        fs::write(path, bytes)
+    }
+
+    /// Read an index from a cache file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+
+        let bytes = fs::read(path)?;
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC || u32_at(8) != VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a litch time index"))
+        }
+
+        // The count is untrusted: a length that overflows is just invalid.
+        let count = usize::try_from(u64_at(32)).ok()
+            .filter(|count| count.checked_mul(16)
+                .and_then(|len| len.checked_add(HEADER_LEN)) == Some(bytes.len()))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Time index is truncated"))?;
+
+        let entries = (0..count)
+            .map(|i| HEADER_LEN + 16 * i)
+            .map(|at| (u64_at(at), u64_at(at + 8)))
+            .collect();
+
+        Ok(Self { 
+            stride: u32_at(12) as usize, 
+            file_len: u64_at(16), 
+            fingerprint: u64_at(24), 
+            entries,
+        })
+    }
+}
+
+/// Cache location for a file's index.
+pub(super) fn cache_path(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(".tidx");
+    PathBuf::from(name)
+}
@@ -0,0 +1,142 @@
+
+//! Historical ITCH files (2-byte length-prefixed messages, 
+//! as distributed by Nasdaq once decompressed).
+
+mod index;
+mod split;
+#[cfg(feature = "parallel")]
+mod parallel;
+
+pub use index::{ TimeIndex, DEFAULT_STRIDE };
+pub use split::{ Splitter, SplitStats };
+#[cfg(feature = "parallel")]
+pub use parallel::Chunk;
+
+use std::fs::File;
+use std::path::{ Path, PathBuf };
+
+use memmap2::Mmap;
+use nsdq_util::NaiveTime;
+
+use crate::{ Error, ItchMessage };
+use crate::frame::split_length_prefixed;
+use crate::msg::{ nanos_of_day, parse_itch_nanos };
+
+/// Memory-mapped length-prefixed ITCH file.
+pub struct ItchFile {
+    path: PathBuf,
+    map: Mmap,
+}
+
+impl ItchFile {
+
+    /// Map a file into memory.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        // SAFETY: Historical files are read-only and not modified while mapped.
+        let map = unsafe { Mmap::map(&file)? };
+
+        Ok(Self { path, map })
+    }
+
+    /// Path the file was opened from.
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// The raw file contents.
+    pub fn bytes(&self) -> &[u8] { &self.map }
+
+    /// Iterate over every message from the start of the file.
+    pub fn messages(&self) -> Messages<'_> {
+        self.messages_from(0)
+    }
+
+    /// Iterate over messages starting at a byte offset, 
+    /// which must be the start of a length prefix.
+    pub fn messages_from(&self, offset: usize) -> Messages<'_> {
+        Messages { bytes: &self.map, offset: offset.min(self.map.len()) }
+    }
+
+    /// Build a sparse index with an entry every `stride` messages.
+    pub fn build_index(&self, stride: usize) -> TimeIndex {
+        TimeIndex::build(&self.map, stride)
+    }
+
+    /// Load the index cached next to the file (`<file>.tidx`), 
+    /// or build it on a first pass and write the cache.
+    /// A cache that does not match the file contents or stride is rebuilt.
+    /// Writing the cache is best-effort (e.g. the directory may be read-only).
+    pub fn index(&self, stride: usize) -> TimeIndex {
+
+        let cache = index::cache_path(&self.path);
+        let cached = TimeIndex::load(&cache).ok().filter(|index| 
+            index.matches(&self.map) && index.stride() == stride
+        );
+        if let Some(index) = cached {
+            return index
+        }
+
+        let index = self.build_index(stride);
+        let _ = index.save(&cache);
+        index
+    }
+
+    /// Iterate from the first message generated at or after `time`.
+    pub fn seek(&self, index: &TimeIndex, time: NaiveTime) -> Messages<'_> {
+
+        let target = nanos_of_day(time);
+        let mut messages = self.messages_from(index.offset_before(target) as usize);
+        messages.skip_before(target);
+        messages
+    }
+}
+
+/// Iterator over the messages of an `ItchFile`.
+/// A trailing partial message ends iteration.
+pub struct Messages<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Messages<'a> {
+
+    /// Byte offset of the next message's length prefix.
+    pub fn offset(&self) -> usize { self.offset }
+
+    /// Raw bytes of the next message, without advancing.
+    fn peek_raw(&self) -> Option<&'a [u8]> {
+        split_length_prefixed(&self.bytes[self.offset..]).map(|(msg, _)| msg)
+    }
+
+    /// Advance past messages generated before `nanos`.
+    fn skip_before(&mut self, nanos: u64) {
+        while let Some(msg) = self.peek_raw() {
+            match message_nanos(msg) {
+                Some(t) if t >= nanos => break,
+                _ => self.offset += 2 + msg.len(),
+            }
+        }
+    }
+
+    /// Next message as raw bytes (without its length prefix).
+    pub fn next_raw(&mut self) -> Option<&'a [u8]> {
+        let msg = self.peek_raw()?;
+        self.offset += 2 + msg.len();
+        Some(msg)
+    }
+}
+
+impl Iterator for Messages<'_> {
+
+    type Item = Result<ItchMessage, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_raw().map(ItchMessage::from_bytes)
+    }
+}
+
+/// Timestamp of a raw message, read without parsing the body.
+pub(crate) fn message_nanos(msg: &[u8]) -> Option<u64> {
+    parse_itch_nanos(msg.get(5..)?).ok().map(|(_, nanos)| nanos)
+}
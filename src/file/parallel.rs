@@ -0,0 +1,178 @@
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::{ Error, ItchMessage };
+use crate::frame::split_length_prefixed;
+use super::{ ItchFile, Messages, TimeIndex };
+
+/// Contiguous run of whole messages within a file.
+#[derive(Debug, Clone, Copy)]
+pub struct Chunk<'a> {
+    /// Byte offset of the chunk in the file.
+    pub offset: usize,
+    /// Length-prefixed messages in the chunk.
+    pub bytes: &'a [u8],
+}
+
+impl<'a> Chunk<'a> {
+
+    /// Iterate over the messages in the chunk.
+    pub fn messages(&self) -> Messages<'a> {
+        Messages { bytes: self.bytes, offset: 0 }
+    }
+
+    /// Parse every message in the chunk.
+    pub fn parse(&self) -> Result<Vec<ItchMessage>, Error> {
+        self.messages().collect()
+    }
+
+    /// Parse messages up to the first error.
+    fn parse_partial(&self) -> (Vec<ItchMessage>, Option<Error>) {
+
+        let mut msgs = Vec::new();
+        for msg in self.messages() {
+            match msg {
+                Ok(msg) => msgs.push(msg),
+                Err(e) => return (msgs, Some(e)),
+            }
+        }
+        (msgs, None)
+    }
+}
+
+impl ItchFile {
+
+    /// Split the file into roughly `count` chunks of similar size. 
+    ///
+    /// The length-prefixed format has no sync marker, so boundaries are 
+    /// found by stepping over the length prefixes from the start of the file
+    /// (which reads two bytes per message, without parsing).
+    pub fn chunks(&self, count: usize) -> Vec<Chunk<'_>> {
+
+        let bytes = self.bytes();
+        let target = bytes.len().div_ceil(count.max(1)).max(1);
+
+        let mut cuts = vec![0];
+        let mut rest = bytes;
+        while let Some((_, next)) = split_length_prefixed(rest) {
+            rest = next;
+            let offset = bytes.len() - rest.len();
+            if offset - cuts.last().unwrap() >= target {
+                cuts.push(offset);
+            }
+        }
+
+        self.chunks_at(cuts, bytes.len() - rest.len())
+    }
+
+    /// Split the file into at most `count` chunks at offsets taken 
+    /// from a time index, which are already message boundaries.
+    pub fn chunks_indexed(&self, index: &TimeIndex, count: usize) -> Vec<Chunk<'_>> {
+
+        let entries = index.entries();
+        let step = entries.len().div_ceil(count.max(1)).max(1);
+        let cuts = entries.iter()
+            .step_by(step)
+            .map(|&(_, offset)| offset as usize)
+            .collect();
+
+        self.chunks_at(cuts, self.bytes().len())
+    }
+
+    fn chunks_at(&self, mut cuts: Vec<usize>, end: usize) -> Vec<Chunk<'_>> {
+
+        cuts.retain(|&cut| cut < end);
+        if cuts.first() != Some(&0) {
+            cuts.insert(0, 0);
+        }
+        cuts.push(end);
+
+        cuts.windows(2)
+            .filter(|w| w[0] < w[1])
+            .map(|w| Chunk { offset: w[0], bytes: &self.bytes()[w[0]..w[1]] })
+            .collect()
+    }
+
+    /// Fold every message into per-chunk accumulators in parallel,
+    /// then reduce them. `reduce` receives results in file order.
+    pub fn par_fold<T, I, F, R>(
+        &self, 
+        chunks: &[Chunk<'_>], 
+        init: I, 
+        fold: F, 
+        reduce: R,
+    ) -> Result<T, Error> 
+    where
+        T: Send,
+        I: Fn() -> T + Sync + Send,
+        F: Fn(&mut T, ItchMessage) + Sync + Send,
+        R: Fn(T, T) -> T + Sync + Send,
+    {
+        chunks.par_iter()
+            .map(|chunk| {
+                let mut acc = init();
+                for msg in chunk.messages() {
+                    fold(&mut acc, msg?);
+                }
+                Ok(acc)
+            })
+            .try_reduce(&init, |a, b| Ok(reduce(a, b)))
+    }
+
+    /// Aggregate messages per `stock_locate` in parallel.
+    /// `merge` combines the accumulators of the same locate across chunks, 
+    /// earlier chunk first.
+    pub fn par_by_locate<T, F, M>(
+        &self, 
+        chunks: &[Chunk<'_>], 
+        fold: F, 
+        merge: M,
+    ) -> Result<HashMap<u16, T>, Error> 
+    where
+        T: Default + Send,
+        F: Fn(&mut T, &ItchMessage) + Sync + Send,
+        M: Fn(&mut T, T) + Sync + Send,
+    {
+        self.par_fold(
+            chunks, 
+            HashMap::new, 
+            |map: &mut HashMap<u16, T>, msg| {
+                let acc = map.entry(msg.metadata().stock_locate).or_default();
+                fold(acc, &msg)
+            },
+            |mut a, b| {
+                for (locate, acc) in b {
+                    match a.get_mut(&locate) {
+                        Some(existing) => merge(existing, acc),
+                        None => { a.insert(locate, acc); },
+                    }
+                }
+                a
+            },
+        )
+    }
+
+    /// Iterate over every message in file order, parsing up to `window` 
+    /// chunks ahead in parallel. Stops after the first error.
+    pub fn par_messages<'a>(
+        &'a self, 
+        chunks: &'a [Chunk<'a>], 
+        window: usize,
+    ) -> impl Iterator<Item = Result<ItchMessage, Error>> + 'a {
+
+        chunks.chunks(window.max(1))
+            .flat_map(|group| {
+                group.par_iter()
+                    .map(Chunk::parse_partial)
+                    .collect::<Vec<_>>()
+            })
+            .scan(false, |failed, (msgs, error)| {
+                if *failed { return None }
+                *failed = error.is_some();
+                Some(msgs.into_iter().map(Ok).chain(error.map(Err)))
+            })
+            .flatten()
+    }
+}
@@ -0,0 +1,214 @@
+
+use std::collections::{ HashMap, HashSet };
+use std::fs::{ self, File, OpenOptions };
+use std::io::Write;
+use std::path::{ Path, PathBuf };
+
+use crate::{ Error, ItchMessage };
+use crate::msg::ItchMetadata;
+use super::ItchFile;
+
+/// Bytes buffered per symbol before they are appended to its file.
+pub const DEFAULT_BUFFER_LIMIT: usize = 64 * 1024;
+
+/// Output file for one symbol.
+struct Output {
+    path: PathBuf,
+    buf: Vec<u8>,
+    created: bool,
+}
+
+impl Output {
+
+    fn push(&mut self, msg: &[u8]) {
+        self.buf.extend_from_slice(&(msg.len() as u16).to_be_bytes());
+        self.buf.extend_from_slice(msg);
+    }
+
+    /// Append the buffer to the file. 
+    /// Files are only held open while writing, since a day has 
+    /// more symbols than most open file limits allow.
+    fn flush(&mut self) -> Result<(), Error> {
+
+        if self.created && self.buf.is_empty() { return Ok(()) }
+
+        let mut file = match self.created {
+            true => OpenOptions::new().append(true).open(&self.path)?,
+            false => File::create(&self.path)?,
+        };
+        file.write_all(&self.buf)?;
+
+        self.created = true;
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+/// Counts reported once a split is finished.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitStats {
+    /// Messages read from the input.
+    pub messages: u64,
+    /// Messages written to exactly one symbol's file.
+    pub routed: u64,
+    /// Market-wide messages copied into every symbol's file.
+    pub market_wide: u64,
+    /// Messages for locates without a (selected) directory record.
+    pub skipped: u64,
+    /// Files written, sorted by path.
+    pub files: Vec<PathBuf>,
+}
+
+/// Splits a day of raw messages into one length-prefixed file per symbol 
+/// (`<dir>/<SYMBOL>.itch`), each of which can be replayed on its own.
+///
+/// Symbols are resolved from `StockDirectory` records, and every message 
+/// carrying that locate (including `OrderExecuted`, `OrderDeleted` and the 
+/// other messages without a symbol) is routed to the symbol's file. 
+/// Each file starts with the market-wide messages sent before its directory 
+/// record, and receives every market-wide message sent after it.
+pub struct Splitter {
+    dir: PathBuf,
+    selected: Option<HashSet<String>>,
+    buffer_limit: usize,
+    market: Vec<Vec<u8>>,
+    outputs: HashMap<u16, Output>,
+    stats: SplitStats,
+}
+
+impl Splitter {
+
+    /// Write per-symbol files into `dir` (created if missing).
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, Error> {
+
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            selected: None,
+            buffer_limit: DEFAULT_BUFFER_LIMIT,
+            market: Vec::new(),
+            outputs: HashMap::new(),
+            stats: SplitStats::default(),
+        })
+    }
+
+    /// Only write files for the given symbols.
+    pub fn symbols<S: AsRef<str>>(mut self, symbols: impl IntoIterator<Item = S>) -> Self {
+        self.selected = Some(symbols.into_iter()
+            .map(|s| s.as_ref().trim().to_string())
+            .collect());
+        self
+    }
+
+    /// Bytes buffered per symbol before they are written out.
+    pub fn buffer_limit(mut self, bytes: usize) -> Self {
+        self.buffer_limit = bytes;
+        self
+    }
+
+    /// Split every message of a file.
+    pub fn split(mut self, input: &ItchFile) -> Result<SplitStats, Error> {
+
+        let mut messages = input.messages();
+        while let Some(msg) = messages.next_raw() {
+            self.push(msg)?;
+        }
+        self.finish()
+    }
+
+    /// Route one raw message (without its length prefix).
+    pub fn push(&mut self, msg: &[u8]) -> Result<(), Error> {
+
+        let (_, metadata) = msg.get(1..)
+            .and_then(|body| ItchMetadata::parse(body).ok())
+            .ok_or_else(|| Error::message(msg))?;
+        self.stats.messages += 1;
+
+        // Same kinds as `ItchMessage::is_market_wide`, matched by tag 
+        // to avoid parsing every message.
+        if matches!(msg[0], b'S' | b'V' | b'W') {
+            self.market.push(msg.to_vec());
+            for output in self.outputs.values_mut() {
+                output.push(msg);
+            }
+            self.stats.market_wide += 1;
+            return self.flush_full()
+        }
+
+        if msg[0] == b'R' {
+            self.open(metadata.stock_locate, msg)?;
+        }
+
+        match self.outputs.get_mut(&metadata.stock_locate) {
+            Some(output) => {
+                output.push(msg);
+                self.stats.routed += 1;
+                if output.buf.len() >= self.buffer_limit {
+                    output.flush()?;
+                }
+            },
+            None => self.stats.skipped += 1,
+        }
+
+        Ok(())
+    }
+
+    /// Start a symbol's file from its directory record.
+    fn open(&mut self, locate: u16, directory: &[u8]) -> Result<(), Error> {
+
+        let ItchMessage::StockDirectory { body, .. } = ItchMessage::from_bytes(directory)? 
+        else { return Ok(()) };
+
+        let symbol = body.stock.to_str();
+        let selected = self.selected.as_ref().is_none_or(|s| s.contains(symbol));
+        if !selected || self.outputs.contains_key(&locate) {
+            return Ok(())
+        }
+
+        let mut output = Output {
+            path: self.dir.join(format!("{}.itch", file_name(symbol))),
+            buf: Vec::new(),
+            created: false,
+        };
+        for msg in &self.market {
+            output.push(msg);
+        }
+        self.outputs.insert(locate, output);
+
+        Ok(())
+    }
+
+    fn flush_full(&mut self) -> Result<(), Error> {
+        for output in self.outputs.values_mut() {
+            if output.buf.len() >= self.buffer_limit {
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write out all buffered messages.
+    pub fn finish(mut self) -> Result<SplitStats, Error> {
+
+        for output in self.outputs.values_mut() {
+            output.flush()?;
+        }
+
+        self.stats.files = self.outputs.into_values().map(|o| o.path).collect();
+        self.stats.files.sort();
+        Ok(self.stats)
+    }
+}
+
+/// Symbols may contain spaces or punctuation (e.g. `BRK A`), 
+/// which are replaced to keep file names portable.
+fn file_name(symbol: &str) -> String {
+    symbol.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
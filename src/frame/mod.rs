@@ -0,0 +1,36 @@
+
+//! Transport framing that carries ITCH messages.
+//!
+//! ITCH messages are delivered inside MoldUDP64 packets (multicast),
+//! SoupBinTCP packets (TCP), or with a plain 2-byte length prefix 
+//! (historical files and some TCP replays).
+
+/// MoldUDP64 packets and sequence tracking.
+pub mod mold;
+/// SoupBinTCP packets.
+pub mod soup;
+
+pub use mold::{ MoldPacket, MoldMessages, Sequencer };
+pub use soup::SoupPacket;
+
+/// Split the next length-prefixed message off the front of `input`.
+/// Returns `None` if `input` does not yet hold a complete message.
+pub fn split_length_prefixed(input: &[u8]) -> Option<(&[u8], &[u8])> {
+
+    let len = u16::from_be_bytes(input.get(..2)?.try_into().ok()?) as usize;
+    let msg = input.get(2..2 + len)?;
+
+    Some((msg, &input[2 + len..]))
+}
+
+/// Iterate over every complete length-prefixed message in `input`.
+/// A trailing partial message is ignored.
+pub fn length_prefixed(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        let (msg, next) = split_length_prefixed(rest)?;
+        rest = next;
+        Some(msg)
+    })
+}
@@ -0,0 +1,133 @@
+
+/// MoldUDP64 downstream packet, as received in a single datagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoldPacket<'a> {
+
+    /// Session the packet belongs to (typically the trading date).
+    pub session: [u8; 10],
+    /// Sequence number of the first message in the packet.
+    pub sequence: u64,
+    /// Number of messages in the packet.
+    pub count: u16,
+    /// Message blocks (each prefixed with a 2-byte length).
+    pub blocks: &'a [u8],
+}
+
+impl<'a> MoldPacket<'a> {
+
+    /// Size of the packet header in bytes.
+    pub const HEADER_LEN: usize = 20;
+
+    /// Message count used to signal the end of the session.
+    pub const END_OF_SESSION: u16 = 0xFFFF;
+
+    /// Read the header of a datagram. 
+    /// Returns `None` if the datagram is shorter than a header.
+    pub fn parse(datagram: &'a [u8]) -> Option<Self> {
+
+        let header = datagram.get(..Self::HEADER_LEN)?;
+
+        Some(Self {
+            session: header[..10].try_into().ok()?,
+            sequence: u64::from_be_bytes(header[10..18].try_into().ok()?),
+            count: u16::from_be_bytes(header[18..20].try_into().ok()?),
+            blocks: &datagram[Self::HEADER_LEN..],
+        })
+    }
+
+    /// True if the packet carries no messages.
+    pub fn is_heartbeat(&self) -> bool { self.count == 0 }
+
+    /// True if the packet marks the end of the session.
+    pub fn is_end_of_session(&self) -> bool { 
+        self.count == Self::END_OF_SESSION 
+    }
+
+    /// Iterate over the messages in the packet.
+    pub fn messages(&self) -> MoldMessages<'a> {
+
+        let count = if self.is_end_of_session() { 0 } else { self.count };
+        MoldMessages { blocks: self.blocks, remaining: count }
+    }
+}
+
+/// Iterator over the messages in a `MoldPacket`.
+#[derive(Debug, Clone)]
+pub struct MoldMessages<'a> {
+    blocks: &'a [u8],
+    remaining: u16,
+}
+
+impl<'a> Iterator for MoldMessages<'a> {
+
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        if self.remaining == 0 { return None }
+
+        let Some((msg, rest)) = super::split_length_prefixed(self.blocks) else {
+            // Truncated packet: stop rather than read garbage.
+            self.remaining = 0;
+            return None
+        };
+
+        self.blocks = rest;
+        self.remaining -= 1;
+        Some(msg)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
+    }
+}
+
+/// Tracks MoldUDP64 sequence numbers across packets,
+/// dropping duplicates (e.g. from A/B feed arbitration) and counting gaps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sequencer {
+    expected: Option<u64>,
+    missed: u64,
+    duplicates: u64,
+}
+
+impl Sequencer {
+
+    /// Create a sequencer that accepts whatever sequence arrives first.
+    pub fn new() -> Self { Self::default() }
+
+    /// Create a sequencer expecting the given sequence number next.
+    pub fn starting_at(sequence: u64) -> Self {
+        Self { expected: Some(sequence), ..Self::default() }
+    }
+
+    /// Register a packet, returning how many of its leading messages 
+    /// have already been seen and should be skipped.
+    pub fn accept(&mut self, packet: &MoldPacket) -> usize {
+
+        if packet.is_end_of_session() { return 0 }
+
+        let count = packet.count as u64;
+        let expected = *self.expected.get_or_insert(packet.sequence);
+
+        let skip = if packet.sequence > expected {
+            self.missed += packet.sequence - expected;
+            0
+        } else {
+            (expected - packet.sequence).min(count)
+        };
+
+        self.duplicates += skip;
+        self.expected = Some(expected.max(packet.sequence + count));
+        skip as usize
+    }
+
+    /// Sequence number expected in the next packet.
+    pub fn expected(&self) -> Option<u64> { self.expected }
+
+    /// Total number of messages lost to sequence gaps.
+    pub fn missed(&self) -> u64 { self.missed }
+
+    /// Total number of messages discarded as duplicates.
+    pub fn duplicates(&self) -> u64 { self.duplicates }
+}
@@ -0,0 +1,95 @@
+
+/// SoupBinTCP packet, framed by a 2-byte length and a 1-byte type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoupPacket<'a> {
+
+    /// Sequenced data: carries a single ITCH message.
+    SequencedData(&'a [u8]),
+    /// Unsequenced data.
+    UnsequencedData(&'a [u8]),
+    /// Server heartbeat.
+    ServerHeartbeat,
+    /// End of session: no more data will be sent.
+    EndOfSession,
+    /// Login accepted, with the session and the next sequence number.
+    LoginAccepted { session: &'a [u8], sequence: u64 },
+    /// Login rejected, with the reject reason code.
+    LoginRejected(u8),
+    /// Debug text.
+    Debug(&'a [u8]),
+    /// Any other packet type (including client-to-server packets).
+    Other(u8, &'a [u8]),
+}
+
+impl<'a> SoupPacket<'a> {
+
+    /// Split the next packet off the front of `input`.
+    /// Returns `Ok(None)` if `input` does not yet hold a complete packet.
+    pub fn split(input: &'a [u8]) -> Result<Option<(Self, &'a [u8])>, crate::Error> {
+
+        let Some((frame, rest)) = super::split_length_prefixed(input) else {
+            return Ok(None)
+        };
+
+        let (&kind, payload) = frame.split_first()
+            .ok_or(crate::Error::Frame("Empty SoupBinTCP packet"))?;
+
+        let packet = match kind {
+            b'S' => SoupPacket::SequencedData(payload),
+            b'U' => SoupPacket::UnsequencedData(payload),
+            b'H' => SoupPacket::ServerHeartbeat,
+            b'Z' => SoupPacket::EndOfSession,
+            b'+' => SoupPacket::Debug(payload),
+            b'J' => SoupPacket::LoginRejected(payload.first().copied()
+                .ok_or(crate::Error::Frame("Login reject without reason"))?),
+            b'A' => {
+                if payload.len() != 30 {
+                    return Err(crate::Error::Frame("Login accept length"))
+                }
+                let (session, seq) = payload.split_at(10);
+                let sequence = std::str::from_utf8(seq).ok()
+                    .and_then(|s| s.trim().parse().ok())
+                    .ok_or(crate::Error::Frame("Login accept sequence"))?;
+                SoupPacket::LoginAccepted { session, sequence }
+            },
+            other => SoupPacket::Other(other, payload),
+        };
+
+        Ok(Some((packet, rest)))
+    }
+}
+
+/// Encode a login request packet.
+/// Fields are padded (or truncated) to their fixed SoupBinTCP widths;
+/// an empty `session` requests the currently active session, 
+/// and a `sequence` of zero requests the most recent messages.
+pub fn login_request(
+    username: &str, 
+    password: &str, 
+    session: &str, 
+    sequence: u64,
+) -> Vec<u8> {
+
+    let mut packet = vec![0, 47, b'L'];
+    packet.extend(pad::<6>(username, false));
+    packet.extend(pad::<10>(password, false));
+    packet.extend(pad::<10>(session, true));
+    packet.extend(pad::<20>(&sequence.to_string(), true));
+    packet
+}
+
+/// Encode a client heartbeat packet.
+pub fn client_heartbeat() -> [u8; 3] { [0, 1, b'R'] }
+
+/// Encode a logout request packet.
+pub fn logout_request() -> [u8; 3] { [0, 1, b'O'] }
+
+// Alphanumeric fields are left-aligned, numeric fields right-aligned.
+fn pad<const N: usize>(s: &str, right_align: bool) -> [u8; N] {
+
+    let mut buf = [b' '; N];
+    let bytes = &s.as_bytes()[..s.len().min(N)];
+    let start = if right_align { N - bytes.len() } else { 0 };
+    buf[start..start + bytes.len()].copy_from_slice(bytes);
+    buf
+}
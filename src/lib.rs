@@ -1,8 +1,57 @@
 
+
 /// Contains a `ItchMessage` type that covers all protocol message variants.
 pub mod msg;
 pub use msg::ItchMessage;
 
+/// Errors for receiving and decoding ITCH data.
+pub mod error;
+pub use error::Error;
+
+/// Transport framing (MoldUDP64, SoupBinTCP, length-prefixed).
+pub mod frame;
+
+/// Async `ItchStream` over tokio UDP and TCP sockets.
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "async")]
+pub use stream::{ ItchStream, TcpFraming };
+
+/// Multicast receiver for the TotalView-ITCH feed.
+#[cfg(target_os = "linux")]
+pub mod feed;
+
+/// Lock-free bounded queue for handing messages between threads.
+pub mod queue;
+
+/// Multi-threaded pipeline that partitions messages by security.
+pub mod shard;
+pub use shard::{ Pipeline, Shard, ShardStopped };
+
+/// Trading dates and market hours for ITCH timestamps.
+pub mod clock;
+pub use clock::TradingDate;
+
+/// Phases of the trading day from `SystemEvent` messages.
+pub mod session;
+pub use session::{ Phase, Session };
+
+/// Order book reconstruction from parsed messages.
+pub mod book;
+
+/// Trackers that follow securities through events spread across messages.
+pub mod track;
+
+/// Memory-mapped historical files with time-based seek.
+pub mod file;
+
+/// Compressed archive indexed by security and time.
+pub mod archive;
+
+/// Arrow `RecordBatch` conversion and Parquet export per message kind.
+#[cfg(feature = "arrow")]
+pub mod export;
+
 // Unit tests for the crate:
 #[cfg(test)] mod test;
 
@@ -0,0 +1,61 @@
+
+//! Command-line tools for historical ITCH files.
+
+use std::process::ExitCode;
+
+use litch::file::{ ItchFile, Splitter };
+
+const USAGE: &str = "\
+Usage: litch <COMMAND>
+
+Commands:
+  split <INPUT> <OUTPUT_DIR> [SYMBOL]...
+      Split a length-prefixed ITCH file into one file per symbol.
+      Writes every symbol unless some are listed.";
+
+fn main() -> ExitCode {
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("split") if args.len() >= 3 => split(&args[1], &args[2], &args[3..]),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2)
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("litch: {e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn split(input: &str, output: &str, symbols: &[String]) -> Result<(), litch::Error> {
+
+    let file = ItchFile::open(input)?;
+    let mut splitter = Splitter::new(output)?;
+    if !symbols.is_empty() {
+        splitter = splitter.symbols(symbols);
+    }
+
+    let stats = splitter.split(&file)?;
+    eprintln!(
+        "{} messages: {} routed, {} market-wide, {} skipped; {} files in {}",
+        stats.messages, 
+        stats.routed, 
+        stats.market_wide, 
+        stats.skipped, 
+        stats.files.len(), 
+        output,
+    );
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
 
+use std::cmp::Ordering;
+use std::time::Duration;
 use nsdq_util::NaiveTime;
 
 /// Data common to all ITCH message types.
@@ -11,26 +13,78 @@ pub struct ItchMetadata {
     /// NASDAQ internal tracking number.
     pub tracking_number: u16,
 
-    /// Time this message was generated.
-    pub timestamp: NaiveTime,
+    /// Nanoseconds since midnight at which this message was generated.
+    /// Kept raw so that latency arithmetic does not pay for time conversion;
+    /// use `timestamp` for a `NaiveTime`.
+    pub nanos: u64,
 }
 
-use nom::number::streaming::be_u16;
-use nsdq_util::parse_itch_time_bold;
+use nom::{ bytes::streaming::take, number::streaming::be_u16 };
 
 impl ItchMetadata {
+
     pub(crate) fn parse(input: &[u8]) -> nom::IResult<&[u8], Self> {
 
         let (input, stock_locate) = be_u16(input)?;
         let (input, tracking_number) = be_u16(input)?;
-        let (input, timestamp) = parse_itch_time_bold(input)?;
+        let (input, nanos) = parse_itch_nanos(input)?;
 
         Ok((input, Self { 
             stock_locate, 
             tracking_number, 
-            timestamp,
+            nanos,
         }))
     }
+
+    /// Time this message was generated.
+    ///
+    /// # Panics
+    /// Will panic if the timestamp is past the end of the day, 
+    /// which NASDAQ should never send.
+    pub fn timestamp(&self) -> NaiveTime {
+
+        let d = 10u64.pow(9);
+        let secs = (self.nanos / d) as u32;
+        let nano = (self.nanos % d) as u32;
+
+        NaiveTime::from_num_seconds_from_midnight_opt(secs, nano)
+            .expect("Timestamp is a valid time")
+    }
+
+    /// Signed nanoseconds from `earlier` to this message.
+    /// Negative if this message was generated first.
+    pub fn nanos_since(&self, earlier: &Self) -> i64 {
+        self.nanos as i64 - earlier.nanos as i64
+    }
+
+    /// Time elapsed from `earlier` to this message, 
+    /// or zero if this message was generated first.
+    pub fn duration_since(&self, earlier: &Self) -> Duration {
+        Duration::from_nanos(self.nanos.saturating_sub(earlier.nanos))
+    }
+
+    /// Compare messages by the time they were generated.
+    pub fn cmp_time(&self, other: &Self) -> Ordering {
+        self.nanos.cmp(&other.nanos)
+    }
 }
 
+/// Parse a 6-byte ITCH timestamp into nanoseconds since midnight, 
+/// without converting it to a `NaiveTime`.
+pub fn parse_itch_nanos(input: &[u8]) -> nom::IResult<&[u8], u64> {
+
+    let (input, raw) = take(6usize)(input)?;
 
+    let mut buf = [0u8; 8];
+    buf[2..].copy_from_slice(raw);
+
+    Ok((input, u64::from_be_bytes(buf)))
+}
+
+/// Nanoseconds since midnight for a time of day, 
+/// comparable with `ItchMetadata::nanos`.
+pub fn nanos_of_day(time: NaiveTime) -> u64 {
+    time.signed_duration_since(NaiveTime::MIN)
+        .num_nanoseconds()
+        .expect("Time within the day") as u64
+}
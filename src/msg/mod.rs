@@ -2,7 +2,7 @@
 mod metadata;
 mod kinds;
 
-pub use metadata::ItchMetadata;
+pub use metadata::{ ItchMetadata, nanos_of_day, parse_itch_nanos };
 pub use kinds::*;
 
 
@@ -37,18 +37,40 @@ macro_rules! msg_kinds {
                     $(
                         $tag => parse_kind!(input, metadata, $kind),
                     )*
-                    _ => unimplemented!{}
+                    _ => return Err(nom::Err::Error(
+                        nom::error::Error::new(
+                            input, 
+                            nom::error::ErrorKind::Tag
+                        )
+                    )),
                 };
 
                 Ok((input, message))
             }
 
+            /// Parse a single complete message (e.g. one framed by MoldUDP64).
+            pub fn from_bytes(input: &[u8]) -> Result<Self, crate::Error> {
+                Self::parse(input)
+                    .map(|(_, msg)| msg)
+                    .map_err(|_| crate::Error::message(input))
+            }
+
             /// Extract the metadata common to all message types.
             pub fn metadata(&self) -> ItchMetadata {
                 match self {$(
                     Self::$kind { metadata, body: _ } => *metadata,
                 )*}
             }
+
+            /// Name of the message kind (e.g. `"OrderAdded"`).
+            pub fn kind(&self) -> &'static str {
+                match self {$(
+                    Self::$kind { .. } => stringify!($kind),
+                )*}
+            }
+
+            /// Names of all message kinds, in protocol order.
+            pub const KINDS: &'static [&'static str] = &[$(stringify!($kind)),*];
         }
 
     }
@@ -99,3 +121,16 @@ msg_kinds!{
 }
 
 
+
+impl ItchMessage {
+
+    /// True for messages that apply to the whole market rather than a single
+    /// security (`SystemEvent` and the MWCB messages, sent with locate 0).
+    pub fn is_market_wide(&self) -> bool {
+        matches!(self, 
+            Self::SystemEvent { .. } 
+            | Self::MwcbDeclineLevel { .. } 
+            | Self::MwcbStatus { .. }
+        )
+    }
+}
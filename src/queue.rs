@@ -0,0 +1,259 @@
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, AtomicU64, Ordering };
+
+use crate::ItchMessage;
+
+/// Parsed message along with its position in the feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope {
+
+    /// Feed sequence number (e.g. MoldUDP64 sequence) of the message.
+    pub sequence: u64,
+    /// The parsed message.
+    pub message: ItchMessage,
+}
+
+/// What the producer does when the queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for the consumer to make room.
+    Block,
+    /// Discard the value being pushed.
+    DropNewest,
+    /// Discard the oldest queued value to make room, 
+    /// so the consumer always sees the most recent data.
+    DropOldest,
+}
+
+// Keeps the producer and consumer indices on separate cache lines.
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+// Each slot carries a stamp recording which lap it is in:
+// `stamp == pos` means empty and writable for position `pos`,
+// `stamp == pos + 1` means full with the value for position `pos`.
+struct Slot<T> {
+    stamp: AtomicU64,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+struct Shared<T> {
+    head: CachePadded<AtomicU64>,
+    tail: CachePadded<AtomicU64>,
+    dropped: CachePadded<AtomicU64>,
+    slots: Box<[Slot<T>]>,
+    mask: u64,
+    overflow: Overflow,
+    producer_alive: AtomicBool,
+    consumer_alive: AtomicBool,
+}
+
+// SAFETY: Access to each slot's value is serialized by its stamp.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+
+    fn capacity(&self) -> u64 { self.mask + 1 }
+
+    fn slot(&self, pos: u64) -> &Slot<T> {
+        &self.slots[(pos & self.mask) as usize]
+    }
+
+    fn len(&self) -> usize {
+        let tail = self.tail.0.load(Ordering::Acquire);
+        let head = self.head.0.load(Ordering::Acquire);
+        tail.saturating_sub(head) as usize
+    }
+}
+
+/// Create a bounded single-producer/single-consumer queue.
+/// `capacity` is rounded up to the next power of two.
+///
+/// Designed for `Copy` payloads such as `ItchMessage` or `Envelope`,
+/// so values are moved by plain copies and never need to be dropped.
+pub fn channel<T: Copy + Send>(capacity: usize, overflow: Overflow) -> (Producer<T>, Consumer<T>) {
+
+    let capacity = capacity.max(2).next_power_of_two() as u64;
+    let slots = (0..capacity)
+        .map(|i| Slot { 
+            stamp: AtomicU64::new(i), 
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        })
+        .collect();
+
+    let shared = Arc::new(Shared {
+        head: CachePadded(AtomicU64::new(0)),
+        tail: CachePadded(AtomicU64::new(0)),
+        dropped: CachePadded(AtomicU64::new(0)),
+        slots,
+        mask: capacity - 1,
+        overflow,
+        producer_alive: AtomicBool::new(true),
+        consumer_alive: AtomicBool::new(true),
+    });
+
+    let producer = Producer { shared: shared.clone(), tail: 0 };
+    let consumer = Consumer { shared };
+    (producer, consumer)
+}
+
+/// Sending half of the queue, owned by the parsing thread.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    tail: u64,
+}
+
+impl<T: Copy + Send> Producer<T> {
+
+    /// Push a value, applying the overflow policy if the queue is full.
+    /// Returns the value back if it was not queued 
+    /// (`Overflow::DropNewest` when full, or if the consumer is gone).
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+
+        let shared = &*self.shared;
+        let pos = self.tail;
+        let slot = shared.slot(pos);
+
+        let mut spins = 0u32;
+        loop {
+
+            if !shared.consumer_alive.load(Ordering::Relaxed) {
+                return Err(value)
+            }
+            if slot.stamp.load(Ordering::Acquire) == pos { break }
+
+            let head = shared.head.0.load(Ordering::Acquire);
+            let full = head + shared.capacity() <= pos;
+
+            match shared.overflow {
+                Overflow::DropNewest if full => {
+                    shared.dropped.0.fetch_add(1, Ordering::Relaxed);
+                    return Err(value)
+                },
+                Overflow::DropOldest if full => {
+                    // Claim the oldest value in place of the consumer.
+                    if shared.head.0.compare_exchange(
+                        head, head + 1, Ordering::AcqRel, Ordering::Relaxed
+                    ).is_ok() {
+                        let oldest = shared.slot(head);
+                        oldest.stamp.store(head + shared.capacity(), Ordering::Release);
+                        shared.dropped.0.fetch_add(1, Ordering::Relaxed);
+                    }
+                },
+                // Not full: the consumer is still copying out of this slot.
+                _ => backoff(&mut spins),
+            }
+        }
+
+        // SAFETY: The stamp shows the slot is empty and owned by the producer.
+        unsafe { (*slot.value.get()).write(value); }
+        slot.stamp.store(pos + 1, Ordering::Release);
+
+        self.tail = pos + 1;
+        shared.tail.0.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// Number of values currently queued.
+    pub fn len(&self) -> usize { self.shared.len() }
+
+    /// True if no values are queued.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Maximum number of values the queue can hold.
+    pub fn capacity(&self) -> usize { self.shared.capacity() as usize }
+
+    /// Number of values discarded by the overflow policy.
+    pub fn dropped(&self) -> u64 { self.shared.dropped.0.load(Ordering::Relaxed) }
+}
+
+impl<T> Drop for Producer<T> {
+    fn drop(&mut self) {
+        self.shared.producer_alive.store(false, Ordering::Release);
+    }
+}
+
+/// Receiving half of the queue, owned by a consumer thread.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Copy + Send> Consumer<T> {
+
+    /// Take the oldest value, or `None` if the queue is empty.
+    pub fn try_pop(&mut self) -> Option<T> {
+
+        let shared = &*self.shared;
+        loop {
+
+            let head = shared.head.0.load(Ordering::Acquire);
+            let slot = shared.slot(head);
+            if slot.stamp.load(Ordering::Acquire) != head + 1 {
+                return None
+            }
+
+            // The producer may have dropped this value (`Overflow::DropOldest`).
+            if shared.head.0.compare_exchange(
+                head, head + 1, Ordering::AcqRel, Ordering::Relaxed
+            ).is_err() {
+                continue
+            }
+
+            // SAFETY: The stamp shows the slot is full, and claiming the head
+            // keeps the producer from reusing it until the stamp is released.
+            let value = unsafe { (*slot.value.get()).assume_init_read() };
+            slot.stamp.store(head + shared.capacity(), Ordering::Release);
+            return Some(value)
+        }
+    }
+
+    /// Wait for the next value.
+    /// Returns `None` once the producer is gone and the queue is drained.
+    pub fn pop(&mut self) -> Option<T> {
+
+        let mut spins = 0u32;
+        loop {
+            if let Some(value) = self.try_pop() { return Some(value) }
+            if !self.shared.producer_alive.load(Ordering::Acquire) {
+                return self.try_pop()
+            }
+            backoff(&mut spins);
+        }
+    }
+
+    /// Number of values currently queued.
+    pub fn len(&self) -> usize { self.shared.len() }
+
+    /// True if no values are queued.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Number of values discarded by the overflow policy.
+    pub fn dropped(&self) -> u64 { self.shared.dropped.0.load(Ordering::Relaxed) }
+}
+
+impl<T: Copy + Send> Iterator for Consumer<T> {
+
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> { self.pop() }
+}
+
+impl<T> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        self.shared.consumer_alive.store(false, Ordering::Release);
+    }
+}
+
+// Spin briefly, then yield the thread while waiting.
+fn backoff(spins: &mut u32) {
+    if *spins < 64 {
+        std::hint::spin_loop();
+        *spins += 1;
+    } else {
+        std::thread::yield_now();
+    }
+}
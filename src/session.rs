@@ -0,0 +1,226 @@
+
+use std::fmt;
+use nsdq_util::NaiveTime;
+
+use crate::msg::{ ItchMessage, ItchMetadata, SystemEvent };
+
+/// Phase of the trading day, as set by the last `SystemEvent`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// No `BeginMessages` has been received.
+    #[default]
+    NotStarted,
+    /// After `BeginMessages`, before system hours.
+    StartOfMessages,
+    /// System hours before the market opens (pre-market).
+    SystemHours,
+    /// Regular market hours.
+    MarketHours,
+    /// System hours after the market closes (post-market).
+    AfterMarket,
+    /// Nasdaq has closed; only `BrokenTrade` and `OrderDeleted` may follow.
+    Closed,
+    /// `EndMessages` was received: nothing else is sent for the day.
+    EndOfMessages,
+}
+
+impl Phase {
+
+    /// Phase that a system event begins.
+    pub fn after(event: SystemEvent) -> Self {
+        match event {
+            SystemEvent::BeginMessages => Phase::StartOfMessages,
+            SystemEvent::BeginSystemHours => Phase::SystemHours,
+            SystemEvent::BeginMarketHours => Phase::MarketHours,
+            SystemEvent::EndMarketHours => Phase::AfterMarket,
+            SystemEvent::EndSystemHours => Phase::Closed,
+            SystemEvent::EndMessages => Phase::EndOfMessages,
+        }
+    }
+
+    // System event that begins the phase.
+    fn event(self) -> Option<SystemEvent> {
+        match self {
+            Phase::NotStarted => None,
+            Phase::StartOfMessages => Some(SystemEvent::BeginMessages),
+            Phase::SystemHours => Some(SystemEvent::BeginSystemHours),
+            Phase::MarketHours => Some(SystemEvent::BeginMarketHours),
+            Phase::AfterMarket => Some(SystemEvent::EndMarketHours),
+            Phase::Closed => Some(SystemEvent::EndSystemHours),
+            Phase::EndOfMessages => Some(SystemEvent::EndMessages),
+        }
+    }
+
+    const ALL: [Phase; 7] = [
+        Phase::NotStarted,
+        Phase::StartOfMessages,
+        Phase::SystemHours,
+        Phase::MarketHours,
+        Phase::AfterMarket,
+        Phase::Closed,
+        Phase::EndOfMessages,
+    ];
+}
+
+/// Change of phase caused by a system event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+
+    /// Phase before the event.
+    pub from: Phase,
+    /// Phase after the event.
+    pub to: Phase,
+    /// Time of the system event.
+    pub timestamp: NaiveTime,
+}
+
+/// Something in the feed that doesn't fit the documented sequence of the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionIssue {
+
+    /// Metadata of the offending message.
+    pub metadata: ItchMetadata,
+    /// What was wrong.
+    pub kind: SessionIssueKind,
+}
+
+/// Kinds of session sequencing problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionIssueKind {
+
+    /// A system event repeats or goes back to an earlier phase.
+    /// The phase is left unchanged.
+    OutOfOrder { event: SystemEvent, phase: Phase },
+
+    /// A system event was skipped on the way to a later phase.
+    Missing(SystemEvent),
+
+    /// A message arrived in a phase where none of its kind is expected:
+    /// before `BeginMessages`, after `EndMessages`, or after `EndSystemHours` 
+    /// for anything but `BrokenTrade` and `OrderDeleted`.
+    /// Reported once per phase.
+    Unexpected { kind: &'static str, phase: Phase },
+}
+
+/// Message tagged with the phase it arrived in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tagged {
+
+    /// Phase of the day when the message arrived.
+    /// A system event is tagged with the phase it begins.
+    pub phase: Phase,
+    /// The message itself.
+    pub message: ItchMessage,
+}
+
+type Callback<T> = Box<dyn FnMut(&T) + Send>;
+
+/// State machine following the phases of the trading day 
+/// from `SystemEvent` messages.
+///
+/// Phases only move forward through the day; a `BeginMessages` after 
+/// any later phase starts the next day, with the events skipped on the 
+/// way to `EndMessages` reported as missing. Problems with the sequence are 
+/// collected as `SessionIssue`s, and can also be received by callback.
+#[derive(Default)]
+pub struct Session {
+    phase: Phase,
+    flagged: bool,
+    issues: Vec<SessionIssue>,
+    on_transition: Vec<Callback<Transition>>,
+    on_issue: Vec<Callback<SessionIssue>>,
+}
+
+impl fmt::Debug for Session {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("phase", &self.phase)
+            .field("issues", &self.issues)
+            .field("on_transition", &self.on_transition.len())
+            .field("on_issue", &self.on_issue.len())
+            .finish()
+    }
+}
+
+impl Session {
+
+    /// Create a session that has not started.
+    pub fn new() -> Self { Self::default() }
+
+    /// Call `f` on every change of phase.
+    pub fn on_transition(mut self, f: impl FnMut(&Transition) + Send + 'static) -> Self {
+        self.on_transition.push(Box::new(f));
+        self
+    }
+
+    /// Call `f` on every sequencing problem.
+    pub fn on_issue(mut self, f: impl FnMut(&SessionIssue) + Send + 'static) -> Self {
+        self.on_issue.push(Box::new(f));
+        self
+    }
+
+    /// Current phase.
+    pub fn phase(&self) -> Phase { self.phase }
+
+    /// Sequencing problems found so far.
+    pub fn issues(&self) -> &[SessionIssue] { &self.issues }
+
+    /// Apply a message and pair it with the phase it arrived in.
+    pub fn tag(&mut self, message: ItchMessage) -> Tagged {
+        Tagged { phase: self.update(&message), message }
+    }
+
+    /// Apply a message, returning the phase it arrived in.
+    pub fn update(&mut self, msg: &ItchMessage) -> Phase {
+
+        let metadata = msg.metadata();
+        let ItchMessage::SystemEvent { body: event, .. } = msg else {
+            let expected = match self.phase {
+                Phase::NotStarted | Phase::EndOfMessages => false,
+                Phase::Closed => matches!(msg, 
+                    ItchMessage::BrokenTrade { .. } | ItchMessage::OrderDeleted { .. }
+                ),
+                _ => true,
+            };
+            if !expected && !self.flagged {
+                self.flagged = true;
+                let kind = SessionIssueKind::Unexpected { kind: msg.kind(), phase: self.phase };
+                self.report(SessionIssue { metadata, kind });
+            }
+            return self.phase
+        };
+
+        let from = self.phase;
+        let to = Phase::after(*event);
+        let next_day = from > Phase::StartOfMessages && to == Phase::StartOfMessages;
+
+        if to <= from && !next_day {
+            let kind = SessionIssueKind::OutOfOrder { event: *event, phase: from };
+            self.report(SessionIssue { metadata, kind });
+            return self.phase
+        }
+
+        // Events skipped since the last phase (e.g. when joining mid-day),
+        // or before the end of the previous day.
+        Phase::ALL.iter()
+            .filter(|p| **p > from && (next_day || **p < to))
+            .filter_map(|p| p.event())
+            .for_each(|missing| {
+                let kind = SessionIssueKind::Missing(missing);
+                self.report(SessionIssue { metadata, kind });
+            });
+
+        self.phase = to;
+        self.flagged = false;
+        let transition = Transition { from, to, timestamp: metadata.timestamp() };
+        self.on_transition.iter_mut().for_each(|f| f(&transition));
+
+        self.phase
+    }
+
+    fn report(&mut self, issue: SessionIssue) {
+        self.on_issue.iter_mut().for_each(|f| f(&issue));
+        self.issues.push(issue);
+    }
+}
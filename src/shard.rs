@@ -0,0 +1,134 @@
+
+use std::thread::{ self, JoinHandle };
+
+use crate::ItchMessage;
+use crate::queue::{ self, Envelope, Overflow, Producer };
+
+/// Per-thread message handler for a `Pipeline`.
+/// Implemented for any `FnMut(Envelope)` closure.
+pub trait Shard: Send + 'static {
+
+    /// Handle the next message routed to this shard.
+    fn handle(&mut self, envelope: Envelope);
+}
+
+impl<F: FnMut(Envelope) + Send + 'static> Shard for F {
+    fn handle(&mut self, envelope: Envelope) { self(envelope) }
+}
+
+/// A shard's thread has stopped (it panicked), so it can't take messages.
+/// Call `Pipeline::try_finish` to retrieve the panic payload.
+#[derive(Debug, thiserror::Error)]
+#[error("Shard {shard} stopped unexpectedly")]
+pub struct ShardStopped {
+
+    /// Index of the shard that stopped.
+    pub shard: usize,
+    /// The message that could not be delivered.
+    pub envelope: Envelope,
+}
+
+/// Multi-threaded pipeline that partitions messages by `stock_locate`.
+///
+/// Every message for a given security goes to the same worker thread, 
+/// so per-symbol ordering is kept while symbols are processed in parallel.
+/// Market-wide messages (`SystemEvent`, `MwcbDeclineLevel`, `MwcbStatus`) 
+/// are copied to every shard, in the same position relative to the 
+/// other messages that shard receives.
+pub struct Pipeline<S: Shard> {
+    producers: Vec<Producer<Envelope>>,
+    workers: Vec<JoinHandle<S>>,
+    sequence: u64,
+}
+
+impl<S: Shard> Pipeline<S> {
+
+    /// Spawn `shards` worker threads, each with a queue of `capacity` messages.
+    /// `make_shard` is called with the index of each shard to create its state.
+    pub fn new(shards: usize, capacity: usize, mut make_shard: impl FnMut(usize) -> S) -> Self {
+
+        let shards = shards.max(1);
+        let mut producers = Vec::with_capacity(shards);
+        let mut workers = Vec::with_capacity(shards);
+
+        for index in 0..shards {
+
+            // Book building cannot tolerate lost messages.
+            let (producer, consumer) = queue::channel(capacity, Overflow::Block);
+            let mut shard = make_shard(index);
+
+            let worker = thread::Builder::new()
+                .name(format!("litch-shard-{index}"))
+                .spawn(move || {
+                    for envelope in consumer {
+                        shard.handle(envelope)
+                    }
+                    shard
+                })
+                .expect("Spawn shard thread");
+
+            producers.push(producer);
+            workers.push(worker);
+        }
+
+        Self { producers, workers, sequence: 0 }
+    }
+
+    /// Number of shards.
+    pub fn shards(&self) -> usize { self.producers.len() }
+
+    /// Index of the shard that handles a given security.
+    pub fn shard_of(&self, stock_locate: u16) -> usize {
+        stock_locate as usize % self.producers.len()
+    }
+
+    /// Route a message, numbering it after the previous one.
+    pub fn send_message(&mut self, message: ItchMessage) -> Result<(), ShardStopped> {
+        self.sequence += 1;
+        self.send(Envelope { sequence: self.sequence, message })
+    }
+
+    /// Route a message that already carries a feed sequence number.
+    /// Blocks while the destination queue is full.
+    /// Market-wide messages are still delivered to the other shards 
+    /// if one of them has stopped.
+    pub fn send(&mut self, envelope: Envelope) -> Result<(), ShardStopped> {
+
+        self.sequence = envelope.sequence;
+
+        if envelope.message.is_market_wide() {
+            let mut result = Ok(());
+            for (shard, producer) in self.producers.iter_mut().enumerate() {
+                if producer.push(envelope).is_err() && result.is_ok() {
+                    result = Err(ShardStopped { shard, envelope });
+                }
+            }
+            result
+        } else {
+            let shard = self.shard_of(envelope.message.metadata().stock_locate);
+            self.producers[shard].push(envelope)
+                .map_err(|envelope| ShardStopped { shard, envelope })
+        }
+    }
+
+    /// Close the queues, wait for every shard to finish its backlog,
+    /// and return the shard states in index order.
+    ///
+    /// Panics if a shard thread panicked (see `try_finish`).
+    pub fn finish(self) -> Vec<S> {
+        self.try_finish()
+            .into_iter()
+            .map(|r| r.expect("Shard thread panicked"))
+            .collect()
+    }
+
+    /// Like `finish`, but returns the panic payload of each shard 
+    /// that stopped instead of its state.
+    pub fn try_finish(self) -> Vec<thread::Result<S>> {
+
+        drop(self.producers);
+        self.workers.into_iter()
+            .map(JoinHandle::join)
+            .collect()
+    }
+}
@@ -0,0 +1,209 @@
+
+use std::pin::Pin;
+use std::task::{ Context, Poll, ready };
+
+use futures_core::Stream;
+use tokio::io::{ AsyncRead, ReadBuf };
+use tokio::net::UdpSocket;
+
+use crate::{ Error, ItchMessage };
+use crate::frame::{ self, MoldPacket, Sequencer, SoupPacket };
+
+// Largest possible UDP payload.
+const DATAGRAM_LEN: usize = 65_535;
+
+/// Framing used for ITCH messages on a TCP connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TcpFraming {
+    /// Each message is preceded by its 2-byte big-endian length.
+    LengthPrefixed,
+    /// Messages arrive as SoupBinTCP sequenced data packets.
+    SoupBinTcp,
+}
+
+enum Transport {
+    Udp(UdpSocket),
+    Tcp(Pin<Box<dyn AsyncRead + Send>>, TcpFraming),
+}
+
+/// Asynchronous stream of `ItchMessage`s received over the network.
+///
+/// All receive state (buffered bytes, partially consumed packets) lives in 
+/// the stream itself, so dropping a pending `next()` call 
+/// (e.g. in `tokio::select!`) never loses data: the stream is cancel-safe.
+pub struct ItchStream {
+    transport: Transport,
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+    pending: u16,
+    sequencer: Sequencer,
+    done: bool,
+}
+
+impl ItchStream {
+
+    fn new(transport: Transport, capacity: usize) -> Self {
+        Self {
+            transport,
+            buf: vec![0u8; capacity],
+            start: 0,
+            end: 0,
+            pending: 0,
+            sequencer: Sequencer::new(),
+            done: false,
+        }
+    }
+
+    /// Receive MoldUDP64 packets from a (bound and possibly joined) socket.
+    /// Duplicate messages are dropped and gaps are counted by the sequencer.
+    pub fn mold_udp(socket: UdpSocket) -> Self {
+        Self::new(Transport::Udp(socket), DATAGRAM_LEN)
+    }
+
+    /// Receive messages from a TCP connection (or any other byte stream).
+    ///
+    /// For SoupBinTCP, log in first (see `frame::soup::login_request`) 
+    /// and keep the write half to send client heartbeats.
+    pub fn tcp(reader: impl AsyncRead + Send + 'static, framing: TcpFraming) -> Self {
+        Self::new(Transport::Tcp(Box::pin(reader), framing), 8 * 1024)
+    }
+
+    /// Sequence tracking for MoldUDP64 (gaps and duplicates).
+    pub fn sequencer(&self) -> &Sequencer { &self.sequencer }
+
+    fn poll_udp(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<ItchMessage, Error>>> {
+
+        let Transport::Udp(socket) = &self.transport else { unreachable!() };
+
+        loop {
+
+            if self.pending > 0 {
+                self.pending -= 1;
+                let Some((msg, rest)) = frame::split_length_prefixed(
+                    &self.buf[self.start..self.end]
+                ) else {
+                    self.pending = 0;
+                    return Poll::Ready(Some(Err(
+                        Error::Frame("Truncated MoldUDP64 packet")
+                    )))
+                };
+                let parsed = ItchMessage::from_bytes(msg);
+                self.start = self.end - rest.len();
+                return Poll::Ready(Some(parsed))
+            }
+
+            if self.done { return Poll::Ready(None) }
+
+            let mut read = ReadBuf::new(&mut self.buf);
+            ready!(socket.poll_recv(cx, &mut read))?;
+            let len = read.filled().len();
+
+            let Some(packet) = MoldPacket::parse(&self.buf[..len]) else {
+                return Poll::Ready(Some(Err(
+                    Error::Frame("Datagram shorter than MoldUDP64 header")
+                )))
+            };
+
+            if packet.is_end_of_session() {
+                self.done = true;
+                continue
+            }
+
+            let skip = self.sequencer.accept(&packet);
+            let skipped: usize = packet.messages().take(skip)
+                .map(|m| m.len() + 2)
+                .sum();
+
+            self.start = MoldPacket::HEADER_LEN + skipped;
+            self.end = len;
+            self.pending = packet.count - skip as u16;
+        }
+    }
+
+    fn poll_tcp(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<ItchMessage, Error>>> {
+
+        loop {
+
+            let Transport::Tcp(reader, framing) = &mut self.transport else { 
+                unreachable!() 
+            };
+            let available = &self.buf[self.start..self.end];
+
+            match framing {
+                TcpFraming::LengthPrefixed => {
+                    if let Some((msg, rest)) = frame::split_length_prefixed(available) {
+                        let parsed = ItchMessage::from_bytes(msg);
+                        self.start = self.end - rest.len();
+                        return Poll::Ready(Some(parsed))
+                    }
+                },
+                TcpFraming::SoupBinTcp => match SoupPacket::split(available) {
+                    Ok(Some((packet, rest))) => {
+                        self.start = self.end - rest.len();
+                        match packet {
+                            SoupPacket::SequencedData(msg) => {
+                                return Poll::Ready(Some(ItchMessage::from_bytes(msg)))
+                            },
+                            SoupPacket::EndOfSession => self.done = true,
+                            SoupPacket::LoginRejected(_) => {
+                                self.done = true;
+                                return Poll::Ready(Some(Err(
+                                    Error::Frame("SoupBinTCP login rejected")
+                                )))
+                            },
+                            _ => {},
+                        }
+                        continue
+                    },
+                    Ok(None) => {},
+                    Err(e) => {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(e)))
+                    },
+                },
+            }
+
+            if self.done { return Poll::Ready(None) }
+
+            // Make room for the rest of the partial frame.
+            if self.start > 0 {
+                self.buf.copy_within(self.start..self.end, 0);
+                self.end -= self.start;
+                self.start = 0;
+            }
+            if self.end == self.buf.len() {
+                self.buf.resize(self.buf.len() * 2, 0);
+            }
+
+            let mut read = ReadBuf::new(&mut self.buf[self.end..]);
+            ready!(reader.as_mut().poll_read(cx, &mut read))?;
+            let len = read.filled().len();
+
+            if len == 0 {
+                self.done = true;
+                if self.end > self.start {
+                    return Poll::Ready(Some(Err(
+                        Error::Frame("Connection closed mid-message")
+                    )))
+                }
+                return Poll::Ready(None)
+            }
+            self.end += len;
+        }
+    }
+}
+
+impl Stream for ItchStream {
+
+    type Item = Result<ItchMessage, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+
+        let this = self.get_mut();
+        match this.transport {
+            Transport::Udp(_) => this.poll_udp(cx),
+            Transport::Tcp(..) => this.poll_tcp(cx),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+
+use crate::archive::{ ArchiveReader, ArchiveWriter };
+use crate::msg::*;
+use super::helpers::*;
+
+const SEC: u64 = 1_000_000_000;
+
+fn archive_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("litch-{name}-{}.arc", std::process::id()))
+}
+
+/// Two securities interleaved over an hour, with system events around them.
+fn day() -> Vec<Vec<u8>> {
+
+    let mut msgs = vec![
+        raw_system_event(0, 3 * 3600 * SEC, b'O'),
+        raw_directory(1, 3 * 3600 * SEC, "AAPL"),
+        raw_directory(2, 3 * 3600 * SEC, "MSFT"),
+        raw_system_event(0, 9 * 3600 * SEC, b'Q'),
+    ];
+    for i in 0..3600 {
+        let nanos = (9 * 3600 + i) * SEC;
+        msgs.push(raw_add(1, nanos, 2 * i, 10_0000));
+        msgs.push(raw_add(2, nanos, 2 * i + 1, 20_0000));
+    }
+    msgs.push(raw_system_event(0, 16 * 3600 * SEC, b'M'));
+    msgs
+}
+
+fn write_day(path: &std::path::Path) -> u64 {
+
+    let mut writer = ArchiveWriter::create(path).unwrap().block_size(256);
+    for msg in day() {
+        writer.push(&msg).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn queries_one_symbol_in_range() {
+
+    let path = archive_path("query");
+    assert_eq!(write_day(&path), 7205);
+
+    let reader = ArchiveReader::open(&path).unwrap();
+    assert_eq!(reader.locate("MSFT"), Some(2));
+    assert_eq!(reader.locate("GOOG"), None);
+    assert!(reader.blocks().len() > 3);
+
+    let msgs: Vec<_> = reader
+        .query_symbol("MSFT", time(9 * 3600 + 600)..time(9 * 3600 + 610))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    // Context first: system events and the directory, in feed order.
+    assert!(matches!(msgs[0], ItchMessage::SystemEvent { body: SystemEvent::BeginMessages, .. }));
+    assert!(matches!(msgs[1], ItchMessage::StockDirectory { metadata, .. } if metadata.stock_locate == 2));
+    assert!(matches!(msgs[2], ItchMessage::SystemEvent { body: SystemEvent::BeginMarketHours, .. }));
+
+    let refs: Vec<u64> = msgs[3..].iter()
+        .map(|msg| match msg {
+            ItchMessage::OrderAdded { metadata, body } => {
+                assert_eq!(metadata.stock_locate, 2);
+                body.order_ref_num
+            },
+            other => panic!("Unexpected {other:?}"),
+        })
+        .collect();
+    assert_eq!(refs, (600..610).map(|i| 2 * i + 1).collect::<Vec<_>>());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn includes_directory_from_stream_when_in_range() {
+
+    let raw = archive_path("raw");
+    std::fs::write(&raw, raw_file(&day())).unwrap();
+
+    let path = archive_path("directory");
+    assert_eq!(ArchiveWriter::convert(&raw, &path).unwrap(), 7205);
+    let reader = ArchiveReader::open(&path).unwrap();
+
+    let msgs: Vec<_> = reader.query(1, time(0)..time(9 * 3600 + 1))
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let kinds: Vec<_> = msgs.iter().map(|msg| msg.kind()).collect();
+    assert_eq!(kinds, ["SystemEvent", "StockDirectory", "SystemEvent", "OrderAdded"]);
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&raw).unwrap();
+}
+
+/// Encode a `TradingAction` message for "MSFT".
+fn raw_trading_action(locate: u16, nanos: u64, state: u8) -> Vec<u8> {
+    let mut bytes = vec![b'H'];
+    bytes.extend(locate.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(&nanos.to_be_bytes()[2..]);
+    bytes.extend(stock("MSFT").encode());
+    bytes.extend([state, b' ']);
+    bytes.extend(b"T1  ");
+    bytes
+}
+
+#[test]
+fn includes_latest_status_before_range() {
+
+    let mut msgs = day();
+    msgs.insert(4, raw_trading_action(2, 9 * 3600 * SEC, b'T'));
+    msgs.insert(5, raw_trading_action(2, 9 * 3600 * SEC, b'H'));
+    msgs.insert(6, raw_trading_action(1, 9 * 3600 * SEC, b'H'));
+
+    let path = archive_path("status");
+    let mut writer = ArchiveWriter::create(&path).unwrap().block_size(256);
+    for msg in msgs {
+        writer.push(&msg).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let reader = ArchiveReader::open(&path).unwrap();
+    let msgs: Vec<_> = reader.query(2, time(9 * 3600 + 600)..time(9 * 3600 + 601))
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let kinds: Vec<_> = msgs.iter().map(|msg| msg.kind()).collect();
+    assert_eq!(kinds, ["SystemEvent", "StockDirectory", "SystemEvent", "TradingAction", "OrderAdded"]);
+    assert!(matches!(msgs[3], 
+        ItchMessage::TradingAction { metadata, body } 
+            if metadata.stock_locate == 2 && body.state == TradingState::Halted
+    ));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rejects_oversized_block() {
+
+    let path = archive_path("oversized");
+    write_day(&path);
+
+    // Corrupt the uncompressed length of the first block in the index.
+    let mut bytes = std::fs::read(&path).unwrap();
+    let footer = bytes.len() - 24;
+    let index = u64::from_le_bytes(bytes[footer..footer + 8].try_into().unwrap()) as usize;
+    let first = bytes[index + 4..index + 6].try_into().map(u16::from_le_bytes).unwrap();
+    bytes[index + 38..index + 42].copy_from_slice(&u32::MAX.to_le_bytes());
+    std::fs::write(&path, bytes).unwrap();
+
+    let reader = ArchiveReader::open(&path).unwrap();
+    let mut query = reader.query(first, time(0)..time(86_399));
+    assert!(query.any(|msg| msg.is_err()));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rejects_unfinished_archive() {
+
+    let path = archive_path("unfinished");
+    let mut writer = ArchiveWriter::create(&path).unwrap();
+    writer.push(&raw_system_event(0, 0, b'O')).unwrap();
+    drop(writer);
+
+    assert!(ArchiveReader::open(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,130 @@
+
+use crate::book::{ AnomalyKind, BookChecker, BookView, OwnedView };
+use crate::msg::*;
+use super::helpers::*;
+
+fn kinds(checker: &mut OwnedView<BookChecker>, msg: ItchMessage) -> Vec<AnomalyKind> {
+    checker.update(&msg).into_iter().map(|a| a.kind).collect()
+}
+
+#[test]
+fn flags_order_anomalies() {
+
+    let mut checker = BookChecker::new().owned();
+    assert!(kinds(&mut checker, add(1, 1, Side::Buy, 100, 10_0000)).is_empty());
+
+    assert_eq!(kinds(&mut checker, executed(1, 7, 10)), 
+        [AnomalyKind::UnknownOrder { order_ref_num: 7 }]);
+    assert_eq!(kinds(&mut checker, canceled(1, 1, 150)), 
+        [AnomalyKind::Overfill { order_ref_num: 1, remaining: 100, requested: 150 }]);
+
+    checker.update(&add(1, 2, Side::Buy, 100, 10_0000));
+    assert_eq!(kinds(&mut checker, add(1, 2, Side::Buy, 100, 10_0000)), 
+        [AnomalyKind::DuplicateAdd { order_ref_num: 2 }]);
+
+    checker.update(&add(1, 3, Side::Buy, 100, 10_0000));
+    assert_eq!(kinds(&mut checker, replaced(1, 2, 3, 100, 10_0000)), 
+        [AnomalyKind::ReplaceReusesLive { new_ref_num: 3 }]);
+    assert_eq!(kinds(&mut checker, deleted(1, 2)), 
+        [AnomalyKind::UnknownOrder { order_ref_num: 2 }]);
+
+    // The metadata of the offending message is attached.
+    let anomalies = checker.update(&deleted(4, 99));
+    assert_eq!(anomalies[0].metadata.stock_locate, 4);
+}
+
+// Start the day and open security 1 with its opening cross.
+fn open_market(checker: &mut OwnedView<BookChecker>) {
+    checker.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    checker.update(&system_event(4 * 3600, SystemEvent::BeginSystemHours));
+    checker.update(&system_event(34_200, SystemEvent::BeginMarketHours));
+    checker.update(&cross(1, 34_200, 0, 10_0000, CrossType::Opening));
+}
+
+#[test]
+fn flags_locked_and_crossed_books_while_trading() {
+
+    let mut checker = BookChecker::new().owned();
+    open_market(&mut checker);
+    checker.update(&add(1, 1, Side::Buy, 100, 10_0000));
+
+    assert_eq!(kinds(&mut checker, add(1, 2, Side::Sell, 100, 10_0000)), 
+        [AnomalyKind::Locked { price: price(10_0000) }]);
+    // Still locked: reported once.
+    assert!(kinds(&mut checker, add(1, 3, Side::Sell, 100, 10_0000)).is_empty());
+
+    checker.update(&deleted(1, 2));
+    checker.update(&deleted(1, 3));
+    assert_eq!(kinds(&mut checker, add(1, 4, Side::Sell, 100, 9_9900)), 
+        [AnomalyKind::Crossed { bid: price(10_0000), ask: price(9_9900) }]);
+    checker.update(&deleted(1, 4));
+
+    // Crossing is expected while halted (e.g. before a halt cross).
+    checker.update(&trading_action(
+        1, 10, TradingState::Halted, TradingActionReason::ReasonNotAvailable
+    ));
+    assert!(kinds(&mut checker, add(1, 5, Side::Sell, 100, 9_9900)).is_empty());
+}
+
+#[test]
+fn ignores_crossed_books_outside_continuous_trading() {
+
+    let mut checker = BookChecker::new().owned();
+    checker.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    checker.update(&system_event(4 * 3600, SystemEvent::BeginSystemHours));
+
+    // Pre-market orders cross ahead of the opening cross.
+    checker.update(&add(1, 1, Side::Buy, 100, 10_0100));
+    assert!(kinds(&mut checker, add(1, 2, Side::Sell, 100, 10_0000)).is_empty());
+    checker.update(&system_event(34_200, SystemEvent::BeginMarketHours));
+    assert!(kinds(&mut checker, add(1, 3, Side::Sell, 100, 10_0000)).is_empty());
+
+    // The cross executes the crossed interest; afterwards crossing is reported.
+    checker.update(&cross(1, 34_200, 100, 10_0050, CrossType::Opening));
+    checker.update(&deleted(1, 1));
+    assert_eq!(kinds(&mut checker, add(1, 4, Side::Buy, 100, 10_0100)), 
+        [AnomalyKind::Crossed { bid: price(10_0100), ask: price(10_0000) }]);
+
+    // Not after the close.
+    checker.update(&deleted(1, 4));
+    checker.update(&system_event(57_600, SystemEvent::EndMarketHours));
+    assert!(kinds(&mut checker, add(1, 5, Side::Buy, 100, 10_0100)).is_empty());
+}
+
+// Set the timestamp of a message.
+fn at(secs: u64, mut msg: ItchMessage) -> ItchMessage {
+    if let ItchMessage::OrderAdded { metadata, .. } = &mut msg {
+        metadata.nanos = secs * 1_000_000_000;
+    }
+    msg
+}
+
+#[test]
+fn flags_locked_books_until_the_close() {
+
+    let mut checker = BookChecker::new().owned();
+    open_market(&mut checker);
+    checker.update(&at(57_000, add(1, 1, Side::Buy, 100, 10_0000)));
+
+    // 15:55, in the closing cross period but still trading continuously.
+    assert_eq!(kinds(&mut checker, at(57_300, add(1, 2, Side::Sell, 100, 10_0000))), 
+        [AnomalyKind::Locked { price: price(10_0000) }]);
+}
+
+#[test]
+fn flags_books_still_crossed_when_trading_resumes() {
+
+    let mut checker = BookChecker::new().owned();
+    open_market(&mut checker);
+    checker.update(&trading_action(
+        1, 36_000, TradingState::Halted, TradingActionReason::ReasonNotAvailable
+    ));
+    checker.update(&add(1, 1, Side::Buy, 100, 10_0100));
+    assert!(kinds(&mut checker, add(1, 2, Side::Sell, 100, 10_0000)).is_empty());
+
+    assert_eq!(kinds(&mut checker, trading_action(
+        1, 36_300, TradingState::Trading, TradingActionReason::ReasonNotAvailable
+    )), [AnomalyKind::Crossed { bid: price(10_0100), ask: price(10_0000) }]);
+    // Reported once.
+    assert!(kinds(&mut checker, add(1, 3, Side::Sell, 100, 10_0000)).is_empty());
+}
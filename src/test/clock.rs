@@ -0,0 +1,55 @@
+
+use chrono::{ NaiveDate, Timelike };
+use crate::TradingDate;
+use super::helpers::*;
+
+#[test]
+fn converts_to_utc() {
+
+    // Summer (EDT, UTC-4)
+    let date = TradingDate::from_ymd(2024, 7, 1).unwrap();
+    let open = time(9 * 3600 + 30 * 60);
+    assert_eq!(date.utc(open).to_rfc3339(), "2024-07-01T13:30:00+00:00");
+    assert_eq!(date.utc_nanos(open), 1_719_840_600_000_000_000);
+
+    // Winter (EST, UTC-5)
+    let date = TradingDate::from_ymd(2024, 1, 2).unwrap();
+    assert_eq!(date.utc(open).to_rfc3339(), "2024-01-02T14:30:00+00:00");
+    assert_eq!(date.message_nanos(&meta(1, 9 * 3600 + 30 * 60)), 
+        date.utc_nanos(open));
+}
+
+#[test]
+fn elapsed_time_across_dst() {
+
+    // Clocks jump from 2:00 to 3:00 a.m. on 2024-03-10.
+    let date = TradingDate::from_ymd(2024, 3, 10).unwrap();
+    let three_hours = time(3 * 3600);
+    assert_eq!(date.utc(three_hours).to_rfc3339(), "2024-03-10T08:00:00+00:00");
+    assert_eq!(date.eastern(three_hours).hour(), 4);
+}
+
+#[test]
+fn infers_date() {
+
+    let expected = NaiveDate::from_ymd_opt(2019, 1, 30).unwrap();
+    for name in [
+        "01302019.NASDAQ_ITCH50",
+        "/data/itch/20190130.bin",
+        "S013019-v50-2019-01-30.gz",
+    ] {
+        assert_eq!(
+            TradingDate::from_file_name(name).map(|d| d.date()), 
+            Some(expected),
+            "{name}"
+        );
+    }
+    assert!(TradingDate::from_file_name("feed.bin").is_none());
+
+    let captured = std::time::UNIX_EPOCH 
+        + std::time::Duration::from_secs(1_719_802_800); // 2024-07-01T03:00Z
+    assert_eq!(
+        TradingDate::from_capture(captured).date(),
+        NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()
+    );
+}
@@ -0,0 +1,117 @@
+
+use std::thread;
+use crate::book::{ Book, BookView, LiveBook, PriceLevel };
+use crate::msg::{ ItchMessage, Side };
+use super::helpers::*;
+
+#[test]
+fn aggregates_levels() {
+
+    let mut book = Book::new();
+    book.update(&add(1, 1, Side::Buy, 100, 10_0000));
+    book.update(&add_mpid(1, 2, Side::Buy, 50, 10_0000, "ABCD"));
+    book.update(&add(1, 3, Side::Buy, 10, 9_9900));
+    book.update(&add(1, 4, Side::Sell, 70, 10_0100));
+    book.update(&add(2, 5, Side::Sell, 70, 20_0100));
+
+    let stock = book.stock(1).unwrap();
+    assert_eq!(stock.best_bid(), 
+        Some(PriceLevel { price: price(10_0000), size: 150, orders: 2 }));
+    assert_eq!(stock.best_ask(), 
+        Some(PriceLevel { price: price(10_0100), size: 70, orders: 1 }));
+    assert_eq!(stock.bids().count(), 2);
+
+    // Partial execution keeps the order; deletion removes it.
+    book.update(&executed(1, 1, 40));
+    book.update(&deleted(1, 2));
+    let stock = book.stock(1).unwrap();
+    assert_eq!(stock.best_bid(), 
+        Some(PriceLevel { price: price(10_0000), size: 60, orders: 1 }));
+
+    // Replace moves the order to a new level.
+    book.update(&replaced(1, 1, 6, 60, 10_0050));
+    let stock = book.stock(1).unwrap();
+    assert_eq!(stock.best_bid().unwrap().price, price(10_0050));
+    assert_eq!(stock.bids().count(), 2);
+    assert_eq!(book.last_applied().unwrap().stock_locate, 1);
+}
+
+#[test]
+fn duplicate_ref_replaces_stale_order() {
+
+    let mut book = Book::new();
+    book.update(&add(1, 1, Side::Buy, 100, 10_0000));
+    book.update(&add(1, 2, Side::Sell, 50, 10_0100));
+
+    // A duplicate add removes the stale order first.
+    let update = book.update(&add(1, 1, Side::Buy, 30, 9_9900));
+    assert_eq!(update.iter().map(|c| c.delta).collect::<Vec<_>>(), [-100, 30]);
+    let stock = book.stock(1).unwrap();
+    assert_eq!(stock.bids().collect::<Vec<_>>(), 
+        [PriceLevel { price: price(9_9900), size: 30, orders: 1 }]);
+
+    // So does a replace onto a live reference number.
+    let levels = book.update_levels(&replaced(1, 1, 2, 40, 9_9900));
+    assert_eq!(levels.iter().count(), 2);
+    let stock = book.stock(1).unwrap();
+    assert_eq!(stock.best_ask(), None);
+    assert_eq!(stock.best_bid(), 
+        Some(PriceLevel { price: price(9_9900), size: 40, orders: 1 }));
+    assert_eq!(book.orders().len(), 1);
+}
+
+#[test]
+fn ignores_zero_share_orders() {
+
+    let mut book = Book::new();
+    book.update(&add(1, 1, Side::Buy, 100, 10_0000));
+    book.update(&add(1, 2, Side::Buy, 0, 10_0000));
+    book.update(&add(1, 3, Side::Buy, 0, 9_9900));
+    book.update(&deleted(1, 2));
+
+    let stock = book.stock(1).unwrap();
+    assert_eq!(stock.bids().collect::<Vec<_>>(), 
+        [PriceLevel { price: price(10_0000), size: 100, orders: 1 }]);
+}
+
+fn add_at(nanos: u64) -> ItchMessage {
+    let mut msg = add(3, nanos + 1, Side::Buy, 1, 10_0000);
+    if let ItchMessage::OrderAdded { metadata, .. } = &mut msg {
+        metadata.nanos = nanos;
+        metadata.tracking_number = nanos as u16;
+    }
+    msg
+}
+
+#[test]
+fn readers_see_consistent_snapshots() {
+
+    const UPDATES: u64 = 100_000;
+    let mut live = LiveBook::<5>::new(10).owned();
+
+    let readers: Vec<_> = (0..2).map(|_| {
+        let reader = live.reader();
+        thread::spawn(move || {
+            let mut last = 0;
+            while last < UPDATES - 1 {
+                let Some(snap) = reader.snapshot(3) else { continue };
+                let bid = snap.best_bid().unwrap();
+                assert_eq!(bid.size, bid.orders as u64);
+                assert_eq!(bid.size, snap.nanos + 1);
+                assert_eq!(snap.tracking_number, snap.nanos as u16);
+                assert!(snap.nanos >= last);
+                last = snap.nanos;
+            }
+        })
+    }).collect();
+
+    for nanos in 0..UPDATES {
+        live.update(&add_at(nanos));
+    }
+    for reader in readers { reader.join().unwrap() }
+
+    let reader = live.reader();
+    assert_eq!(reader.version(3), UPDATES);
+    assert!(reader.snapshot(4).is_none());
+    assert!(reader.snapshot(11).is_none());
+}
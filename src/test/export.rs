@@ -0,0 +1,118 @@
+
+use std::fs::File;
+
+use arrow::array::{ 
+    Array, 
+    AsArray,
+    Decimal128Array, 
+    StringArray,
+    UInt64Array,
+};
+use arrow::datatypes::{ DataType, Int16Type, Int32Type, TimeUnit };
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::TradingDate;
+use crate::export::{ ArrowConverter, ParquetExporter };
+use crate::msg::*;
+use super::helpers::*;
+
+#[test]
+fn converts_messages_per_kind() {
+
+    let mut converter = ArrowConverter::new();
+    converter.push(&system_event(1, SystemEvent::BeginMessages));
+    converter.push(&add(5, 1, Side::Buy, 100, 10_0000));
+    assert_eq!(converter.push(&add(6, 2, Side::Sell, 200, 12_3456)), 2);
+    converter.push(&executed(5, 1, 40));
+    assert_eq!(converter.rows("OrderAdded"), Some(2));
+    assert_eq!(converter.rows("Unknown"), None);
+
+    let schema = converter.schema("OrderAdded").unwrap();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, [
+        "timestamp", "stock_locate", "tracking_number",
+        "order_ref_num", "side", "quantity", "stock", "price",
+    ]);
+    assert_eq!(
+        schema.field_with_name("timestamp").unwrap().data_type(), 
+        &DataType::Time64(TimeUnit::Nanosecond)
+    );
+    assert_eq!(
+        schema.field_with_name("price").unwrap().data_type(), 
+        &DataType::Decimal128(10, 4)
+    );
+
+    let batches = converter.finish();
+    let kinds: Vec<_> = batches.iter().map(|(kind, _)| *kind).collect();
+    assert_eq!(kinds, ["SystemEvent", "OrderAdded", "OrderExecuted"]);
+    assert_eq!(converter.rows("OrderAdded"), Some(0));
+
+    let adds = &batches[1].1;
+    assert_eq!(adds.num_rows(), 2);
+
+    let prices = adds.column_by_name("price").unwrap()
+        .as_any().downcast_ref::<Decimal128Array>().unwrap();
+    assert_eq!(prices.value(1), 12_3456);
+    assert_eq!(prices.value_as_string(1), "12.3456");
+
+    let sides = adds.column_by_name("side").unwrap().as_dictionary::<Int16Type>();
+    let side_names = sides.values().as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(side_names.value(sides.keys().value(1) as usize), "Sell");
+
+    let stocks = adds.column_by_name("stock").unwrap().as_dictionary::<Int32Type>();
+    assert_eq!(stocks.values().len(), 1);
+
+    let events = &batches[0].1;
+    let event = events.column_by_name("event").unwrap().as_dictionary::<Int16Type>();
+    let event_names = event.values().as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(event_names.value(0), "BeginMessages");
+}
+
+#[test]
+fn timestamps_anchor_to_trading_date() {
+
+    let date = TradingDate::from_ymd(2019, 1, 30).unwrap();
+    let mut converter = ArrowConverter::with_date(date);
+    converter.push(&system_event(34_200, SystemEvent::BeginMarketHours));
+
+    let (_, batch) = converter.finish().remove(0);
+    let timestamps = batch.column_by_name("timestamp").unwrap()
+        .as_primitive::<arrow::datatypes::TimestampNanosecondType>();
+    // 9:30 a.m. EST is 14:30 UTC.
+    assert_eq!(timestamps.value(0), 1_548_858_600_000_000_000);
+}
+
+#[test]
+fn writes_parquet_per_kind() {
+
+    let dir = std::env::temp_dir()
+        .join(format!("litch-parquet-{}", std::process::id()));
+    let mut exporter = ParquetExporter::new(&dir).unwrap().batch_rows(2);
+
+    for i in 0..5 {
+        exporter.push(&add(5, i, Side::Buy, 100, 10_0000)).unwrap();
+    }
+    exporter.push(&deleted(5, 3)).unwrap();
+
+    let paths = exporter.finish().unwrap();
+    assert_eq!(paths, [
+        dir.join("OrderAdded.parquet"), 
+        dir.join("OrderDeleted.parquet"),
+    ]);
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&paths[0]).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let refs: Vec<u64> = reader
+        .flat_map(|batch| {
+            let batch = batch.unwrap();
+            batch.column_by_name("order_ref_num").unwrap()
+                .as_any().downcast_ref::<UInt64Array>().unwrap()
+                .values().to_vec()
+        })
+        .collect();
+    assert_eq!(refs, [0, 1, 2, 3, 4]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
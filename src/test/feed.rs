@@ -0,0 +1,120 @@
+
+use std::net::{ Ipv4Addr, UdpSocket };
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use crate::feed::FeedConfig;
+use super::helpers::*;
+
+fn loopback_config(group: Ipv4Addr, port: u16) -> FeedConfig {
+    FeedConfig {
+        interface: Ipv4Addr::LOCALHOST,
+        recv_buffer: 1024 * 1024,
+        read_timeout: Some(Duration::from_secs(5)),
+        ..FeedConfig::new(group, port)
+    }
+}
+
+fn sender() -> UdpSocket {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.set_multicast_loop_v4(true).unwrap();
+    let sock = socket2::SockRef::from(&socket);
+    sock.set_multicast_if_v4(&Ipv4Addr::LOCALHOST).unwrap();
+    socket
+}
+
+#[test]
+fn multicast_loopback() {
+
+    let group = Ipv4Addr::new(239, 255, 73, 1);
+    let mut receiver = loopback_config(group, 47_301).bind().unwrap();
+
+    let msgs: Vec<_> = (0..3).map(|i| raw_system_event(0, i, b'O')).collect();
+    let mut end = mold_packet(4, &[]);
+    end[18..20].copy_from_slice(&[0xFF, 0xFF]);
+
+    let sender = sender();
+    let dest = (group, 47_301);
+    sender.send_to(&mold_packet(1, &msgs[..2]), dest).unwrap();
+    sender.send_to(&mold_packet(1, &msgs[..2]), dest).unwrap();
+    sender.send_to(&mold_packet(3, &msgs[2..]), dest).unwrap();
+    sender.send_to(&end, dest).unwrap();
+
+    let mut received = vec![];
+    receiver.run(|msg| {
+        received.push(msg.unwrap());
+        ControlFlow::Continue(())
+    }).unwrap();
+
+    assert_eq!(received.iter().map(|r| r.sequence).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert!(received.iter().all(|r| r.received.is_some()));
+    assert_eq!(receiver.sequencer().duplicates(), 2);
+}
+
+#[test]
+fn source_specific_join() {
+
+    let group = Ipv4Addr::new(232, 1, 73, 2);
+    let config = FeedConfig { 
+        source: Some(Ipv4Addr::LOCALHOST), 
+        ..loopback_config(group, 47_302)
+    };
+    let mut receiver = config.bind().unwrap();
+
+    sender().send_to(&mold_packet(1, &[raw_system_event(0, 0, b'O')]), (group, 47_302))
+        .unwrap();
+
+    let datagram = receiver.recv().unwrap();
+    assert_eq!(datagram.bytes.len(), 20 + 2 + 12);
+    assert!(datagram.received.is_some());
+}
+
+#[test]
+fn batched_receive() {
+
+    use crate::feed::BatchReceiver;
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let mut receiver = BatchReceiver::new(socket, 8, 256).unwrap();
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for seq in 0..50u64 {
+        let msgs = vec![raw_system_event(0, seq, b'O'); 3];
+        sender.send_to(&mold_packet(1 + seq * 3, &msgs), addr).unwrap();
+    }
+    // Larger than the 256-byte buffers: skipped, leaving a gap.
+    let msgs = vec![raw_system_event(0, 0, b'O'); 20];
+    sender.send_to(&mold_packet(151, &msgs), addr).unwrap();
+    // A message that fails to parse.
+    let mut bad = raw_system_event(0, 0, b'O');
+    bad[0] = b'!';
+    sender.send_to(&mold_packet(171, &[bad, raw_system_event(0, 0, b'O')]), addr).unwrap();
+    let mut end = mold_packet(173, &[]);
+    end[18..20].copy_from_slice(&[0xFF, 0xFF]);
+    sender.send_to(&end, addr).unwrap();
+
+    let mut sequences = vec![];
+    let mut errors = 0;
+    receiver.run(|msg| {
+        match msg {
+            Ok(r) => sequences.push(r.sequence),
+            Err(_) => errors += 1,
+        }
+        ControlFlow::Continue(())
+    }).unwrap();
+
+    let mut expected = (1..=150).collect::<Vec<_>>();
+    expected.push(172);
+    assert_eq!(sequences, expected);
+    assert_eq!(errors, 2);
+    assert_eq!(receiver.sequencer().missed(), 20);
+
+    let stats = receiver.stats();
+    assert_eq!(stats.datagrams, 53);
+    assert_eq!(stats.messages, 151);
+    assert_eq!(stats.truncated, 1);
+    assert_eq!(stats.kernel_drops, 0);
+    assert!(stats.batches <= 53);
+}
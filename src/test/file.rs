@@ -0,0 +1,106 @@
+
+use crate::file::{ ItchFile, TimeIndex };
+use crate::msg::*;
+use super::helpers::*;
+
+const SEC: u64 = 1_000_000_000;
+
+fn write_file(name: &str) -> std::path::PathBuf {
+
+    // One order per second from 9:30, with a second order at 10:00:00.
+    let mut msgs = vec![raw_system_event(0, 3 * 3600 * SEC, b'O')];
+    for i in 0..3600 {
+        let nanos = (34_200 + i) * SEC;
+        msgs.push(raw_add(1, nanos, i, 10_0000));
+        if i == 1800 {
+            msgs.push(raw_add(1, nanos, 99_999, 10_0000));
+        }
+    }
+
+    let path = std::env::temp_dir()
+        .join(format!("litch-{name}-{}.itch", std::process::id()));
+    std::fs::write(&path, raw_file(&msgs)).unwrap();
+    path
+}
+
+fn order_ref(msg: ItchMessage) -> u64 {
+    match msg {
+        ItchMessage::OrderAdded { body, .. } => body.order_ref_num,
+        other => panic!("Unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn seeks_to_first_message_at_or_after_time() {
+
+    let path = write_file("seek");
+    let file = ItchFile::open(&path).unwrap();
+    assert_eq!(file.messages().count(), 3602);
+
+    let index = file.build_index(100);
+    assert_eq!(index.entries().len(), 37);
+
+    let mut msgs = file.seek(&index, time(36_000)).map(Result::unwrap);
+    assert_eq!(order_ref(msgs.next().unwrap()), 1800);
+    assert_eq!(order_ref(msgs.next().unwrap()), 99_999);
+    assert_eq!(order_ref(msgs.next().unwrap()), 1801);
+
+    // Before the first message, and after the last.
+    assert_eq!(file.seek(&index, time(0)).count(), 3602);
+    assert_eq!(file.seek(&index, time(50_000)).count(), 0);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn caches_index_next_to_file() {
+
+    let path = write_file("cache");
+    let cache = path.with_extension("itch.tidx");
+    let file = ItchFile::open(&path).unwrap();
+
+    let built = file.index(64);
+    assert!(cache.exists());
+    assert_eq!(TimeIndex::load(&cache).unwrap(), built);
+    assert_eq!(file.index(64), built);
+
+    // A different stride replaces the cache.
+    let rebuilt = file.index(128);
+    assert_eq!(rebuilt.stride(), 128);
+    assert_eq!(TimeIndex::load(&cache).unwrap(), rebuilt);
+
+    // So does a different file of the same length.
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    drop(file);
+    std::fs::write(&path, &bytes).unwrap();
+    let file = ItchFile::open(&path).unwrap();
+    assert!(!rebuilt.matches(file.bytes()));
+    assert!(file.index(128).matches(file.bytes()));
+
+    // A corrupt entry count is rejected rather than overflowing.
+    let mut bytes = std::fs::read(&cache).unwrap();
+    bytes[32..40].copy_from_slice(&(u64::MAX / 8).to_le_bytes());
+    std::fs::write(&cache, &bytes).unwrap();
+    assert!(TimeIndex::load(&cache).is_err());
+    assert!(file.index(128).matches(file.bytes()));
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&cache).unwrap();
+}
+
+#[test]
+fn index_without_writable_cache() {
+
+    let path = write_file("nocache");
+    // A directory in place of the cache file cannot be written.
+    let cache = path.with_extension("itch.tidx");
+    std::fs::create_dir(&cache).unwrap();
+
+    let file = ItchFile::open(&path).unwrap();
+    assert_eq!(file.index(64), file.build_index(64));
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_dir(&cache).unwrap();
+}
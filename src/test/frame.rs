@@ -0,0 +1,82 @@
+
+use crate::frame::*;
+use super::helpers::*;
+
+#[test]
+fn mold_packets() {
+
+    let msgs = vec![raw_system_event(0, 1, b'O'), raw_system_event(0, 2, b'S')];
+    let bytes = mold_packet(5, &msgs);
+    let packet = MoldPacket::parse(&bytes).unwrap();
+
+    assert_eq!(&packet.session, b"SESSION001");
+    assert_eq!(packet.sequence, 5);
+    assert_eq!(packet.messages().collect::<Vec<_>>(), vec![&msgs[0][..], &msgs[1][..]]);
+
+    // Truncated packets stop early instead of reading garbage.
+    let truncated = MoldPacket::parse(&bytes[..bytes.len() - 1]).unwrap();
+    assert_eq!(truncated.messages().count(), 1);
+    assert!(MoldPacket::parse(&bytes[..10]).is_none());
+}
+
+#[test]
+fn sequencer() {
+
+    let msg = raw_system_event(0, 1, b'O');
+    let packet = |seq, n| mold_packet(seq, &vec![msg.clone(); n]);
+
+    let mut seq = Sequencer::new();
+    assert_eq!(seq.accept(&MoldPacket::parse(&packet(10, 2)).unwrap()), 0);
+    // Duplicate packet from the other feed.
+    assert_eq!(seq.accept(&MoldPacket::parse(&packet(10, 2)).unwrap()), 2);
+    // Partial overlap.
+    assert_eq!(seq.accept(&MoldPacket::parse(&packet(11, 3)).unwrap()), 1);
+    // Gap of 3 (14 through 16).
+    assert_eq!(seq.accept(&MoldPacket::parse(&packet(17, 1)).unwrap()), 0);
+
+    assert_eq!(seq.expected(), Some(18));
+    assert_eq!(seq.missed(), 3);
+    assert_eq!(seq.duplicates(), 3);
+}
+
+#[test]
+fn soup_packets() {
+
+    let msg = raw_system_event(0, 1, b'O');
+    let mut bytes = vec![0, 31, b'A'];
+    bytes.extend(b"SESSION001");
+    bytes.extend(format!("{:>20}", 42).as_bytes());
+    bytes.extend([0, 1, b'H']);
+    bytes.extend((msg.len() as u16 + 1).to_be_bytes());
+    bytes.push(b'S');
+    bytes.extend(&msg);
+
+    let (packet, rest) = SoupPacket::split(&bytes).unwrap().unwrap();
+    assert_eq!(packet, SoupPacket::LoginAccepted { session: b"SESSION001", sequence: 42 });
+    let (packet, rest) = SoupPacket::split(rest).unwrap().unwrap();
+    assert_eq!(packet, SoupPacket::ServerHeartbeat);
+    // Incomplete packets wait for more data.
+    assert!(SoupPacket::split(&rest[..4]).unwrap().is_none());
+    let (packet, rest) = SoupPacket::split(rest).unwrap().unwrap();
+    assert_eq!(packet, SoupPacket::SequencedData(&msg));
+    assert!(rest.is_empty());
+
+    let login = soup::login_request("USER", "PASS", "", 1);
+    assert_eq!(login.len(), 49);
+    assert_eq!(&login[..9], b"\0\x2fLUSER  ");
+    assert!(login.ends_with(&b"                   1"[..]));
+}
+
+#[test]
+fn length_prefixed_messages() {
+
+    let mut bytes = vec![];
+    for i in 0..3 {
+        let msg = raw_system_event(0, i, b'O');
+        bytes.extend((msg.len() as u16).to_be_bytes());
+        bytes.extend(msg);
+    }
+
+    assert_eq!(length_prefixed(&bytes).count(), 3);
+    assert_eq!(length_prefixed(&bytes[..bytes.len() - 1]).count(), 2);
+}
@@ -0,0 +1,238 @@
+
+//! Constructors for messages used across the unit tests.
+
+use nsdq_util::{ Mpid, NaiveTime, Price, StockSymbol };
+use crate::msg::*;
+
+pub fn meta(stock_locate: u16, secs: u32) -> ItchMetadata {
+    ItchMetadata {
+        stock_locate,
+        tracking_number: 0,
+        nanos: secs as u64 * 1_000_000_000,
+    }
+}
+
+pub fn price(val: u32) -> Price<u32, 4> {
+    Price::<u32, 4>::new(val).unwrap()
+}
+
+pub fn stock(s: &str) -> StockSymbol {
+    StockSymbol::from(s).unwrap()
+}
+
+pub fn mpid(s: &str) -> Mpid {
+    Mpid::from(s).unwrap()
+}
+
+pub fn add(
+    locate: u16, 
+    order_ref_num: u64, 
+    side: Side, 
+    quantity: u32, 
+    px: u32,
+) -> ItchMessage {
+    ItchMessage::OrderAdded {
+        metadata: meta(locate, 0),
+        body: OrderAdded { 
+            order_ref_num, 
+            side, 
+            quantity, 
+            stock: stock("TEST"), 
+            price: price(px),
+        },
+    }
+}
+
+pub fn add_mpid(
+    locate: u16, 
+    order_ref_num: u64, 
+    side: Side, 
+    quantity: u32, 
+    px: u32,
+    firm: &str,
+) -> ItchMessage {
+    ItchMessage::OrderAddedWithMpid {
+        metadata: meta(locate, 0),
+        body: OrderAddedWithMpid { 
+            order_ref_num, 
+            side, 
+            quantity, 
+            stock: stock("TEST"), 
+            price: price(px),
+            mpid: mpid(firm),
+        },
+    }
+}
+
+pub fn executed(locate: u16, order_ref_num: u64, quantity: u32) -> ItchMessage {
+    ItchMessage::OrderExecuted {
+        metadata: meta(locate, 0),
+        body: OrderExecuted { order_ref_num, quantity, match_number: 0 },
+    }
+}
+
+pub fn canceled(locate: u16, order_ref_num: u64, quantity: u32) -> ItchMessage {
+    ItchMessage::OrderCanceled {
+        metadata: meta(locate, 0),
+        body: OrderCanceled { order_ref_num, quantity },
+    }
+}
+
+pub fn deleted(locate: u16, order_ref_num: u64) -> ItchMessage {
+    ItchMessage::OrderDeleted {
+        metadata: meta(locate, 0),
+        body: OrderDeleted { order_ref_num },
+    }
+}
+
+pub fn replaced(
+    locate: u16, 
+    old_ref_num: u64, 
+    new_ref_num: u64, 
+    quantity: u32, 
+    px: u32,
+) -> ItchMessage {
+    ItchMessage::OrderReplaced {
+        metadata: meta(locate, 0),
+        body: OrderReplaced { 
+            old_ref_num, 
+            new_ref_num, 
+            quantity, 
+            price: price(px),
+        },
+    }
+}
+
+pub fn time(secs: u32) -> NaiveTime {
+    NaiveTime::from_num_seconds_from_midnight_opt(secs, 0).unwrap()
+}
+
+pub fn trading_action(
+    locate: u16, 
+    secs: u32,
+    state: TradingState, 
+    reason: TradingActionReason,
+) -> ItchMessage {
+    ItchMessage::TradingAction {
+        metadata: meta(locate, secs),
+        body: TradingAction { 
+            stock: stock("TEST"), 
+            state, 
+            reserved: ' ', 
+            reason,
+        },
+    }
+}
+
+pub fn cross(
+    locate: u16, 
+    secs: u32, 
+    quantity: u32, 
+    px: u32, 
+    cross_type: CrossType,
+) -> ItchMessage {
+    ItchMessage::CrossTrade {
+        metadata: meta(locate, secs),
+        body: CrossTrade { 
+            order_ref_num: 0,
+            quantity, 
+            stock: stock("TEST"), 
+            price: price(px), 
+            match_number: 1, 
+            cross_type,
+        },
+    }
+}
+
+pub fn trade(locate: u16, secs: u32, px: u32) -> ItchMessage {
+    ItchMessage::MatchTrade {
+        metadata: meta(locate, secs),
+        body: MatchTrade { 
+            order_ref_num: 0, 
+            quantity: 100, 
+            stock: stock("TEST"), 
+            price: price(px), 
+            match_number: 1,
+        },
+    }
+}
+
+// A `StockDirectory` message for a common stock, sent at 03:00.
+pub fn directory(locate: u16, symbol: &str) -> ItchMessage {
+    ItchMessage::from_bytes(&raw_directory(locate, 3 * 3600 * 1_000_000_000, symbol)).unwrap()
+}
+
+/// Encode a `SystemEvent` message as it appears on the wire.
+pub fn raw_system_event(locate: u16, nanos: u64, code: u8) -> Vec<u8> {
+    let mut bytes = vec![b'S'];
+    bytes.extend(locate.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(&nanos.to_be_bytes()[2..]);
+    bytes.push(code);
+    bytes
+}
+
+/// Encode a `StockDirectory` message for a common stock.
+pub fn raw_directory(locate: u16, nanos: u64, symbol: &str) -> Vec<u8> {
+    let mut bytes = vec![b'R'];
+    bytes.extend(locate.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(&nanos.to_be_bytes()[2..]);
+    bytes.extend(stock(symbol).encode());
+    bytes.extend(b"QN");
+    bytes.extend(100u32.to_be_bytes());
+    bytes.extend(b"NCZ PNN1N");
+    bytes.extend(0u32.to_be_bytes());
+    bytes.push(b'N');
+    bytes
+}
+
+/// Encode a buy `OrderAdded` message for "TEST".
+pub fn raw_add(locate: u16, nanos: u64, order_ref_num: u64, px: u32) -> Vec<u8> {
+    let mut bytes = vec![b'A'];
+    bytes.extend(locate.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(&nanos.to_be_bytes()[2..]);
+    bytes.extend(order_ref_num.to_be_bytes());
+    bytes.push(b'B');
+    bytes.extend(100u32.to_be_bytes());
+    bytes.extend(stock("TEST").encode());
+    bytes.extend(px.to_be_bytes());
+    bytes
+}
+
+/// Encode an `OrderDeleted` message.
+pub fn raw_deleted(locate: u16, nanos: u64, order_ref_num: u64) -> Vec<u8> {
+    let mut bytes = vec![b'D'];
+    bytes.extend(locate.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes());
+    bytes.extend(&nanos.to_be_bytes()[2..]);
+    bytes.extend(order_ref_num.to_be_bytes());
+    bytes
+}
+
+/// Concatenate messages with 2-byte length prefixes, as in historical files.
+pub fn raw_file(messages: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for msg in messages {
+        bytes.extend((msg.len() as u16).to_be_bytes());
+        bytes.extend(msg);
+    }
+    bytes
+}
+
+/// Encode a MoldUDP64 packet containing the given messages.
+pub fn mold_packet(sequence: u64, messages: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = b"SESSION001".to_vec();
+    bytes.extend(sequence.to_be_bytes());
+    bytes.extend((messages.len() as u16).to_be_bytes());
+    for msg in messages {
+        bytes.extend((msg.len() as u16).to_be_bytes());
+        bytes.extend(msg);
+    }
+    bytes
+}
+
+pub fn system_event(secs: u32, event: SystemEvent) -> ItchMessage {
+    ItchMessage::SystemEvent { metadata: meta(0, secs), body: event }
+}
@@ -0,0 +1,98 @@
+
+use crate::msg::*;
+use crate::track::{ IpoTracker, IssueEventKind, IssueStatus };
+use super::helpers::*;
+
+#[test]
+fn ipo_lifecycle() {
+
+    let mut tracker = IpoTracker::new();
+
+    let announce = ItchMessage::QuotingPeriodUpdate {
+        metadata: meta(7, 100),
+        body: QuotingPeriodUpdate {
+            release_time: time(40_000),
+            qualifier: IpoQuotationReleaseQualifier::Anticipated,
+            ipo_price: price(20_0000),
+        },
+    };
+
+    // Unrelated messages are not tracked.
+    assert!(tracker.update(&cross(1, 50, 10, 1_0000, CrossType::Halt)).is_none());
+    assert!(tracker.update(&announce).is_some());
+    assert_eq!(tracker.issue(7).unwrap().status, IssueStatus::Anticipated);
+
+    tracker.update(&trading_action(
+        7, 200, TradingState::Halted, TradingActionReason::IpoIssue
+    ));
+    tracker.update(&trading_action(
+        7, 300, TradingState::QuoteOnly, TradingActionReason::IpoSecurityReleased
+    ));
+    tracker.update(&trading_action(
+        7, 400, TradingState::QuoteOnly, 
+        TradingActionReason::IpoPositioningWindowExtension
+    ));
+    tracker.update(&cross(7, 500, 1_000_000, 21_5000, CrossType::Halt));
+
+    let issue = tracker.issue(7).unwrap();
+    assert_eq!(issue.status, IssueStatus::Opened);
+    assert_eq!(issue.stock, Some(stock("TEST")));
+    assert_eq!(issue.release_time, Some(time(40_000)));
+    assert_eq!(issue.ipo_price, Some(price(20_0000)));
+    assert_eq!(issue.extensions, 1);
+    assert_eq!(issue.announced_at(), Some(time(100)));
+    assert_eq!(issue.first_print_at(), Some(time(500)));
+    assert_eq!(issue.timeline.len(), 5);
+    assert!(matches!(
+        issue.opening_cross.unwrap().kind,
+        IssueEventKind::OpeningCross { quantity: 1_000_000, .. }
+    ));
+
+    // Later activity is not part of the lifecycle.
+    assert!(tracker.update(&trading_action(
+        7, 600, TradingState::Halted, TradingActionReason::HaltNewsPending
+    )).is_none());
+}
+
+#[test]
+fn dlcr_release() {
+
+    let mut tracker = IpoTracker::new();
+
+    let dlcr = |secs: u32, near: u32| ItchMessage::DirectListingWithCapitalRaise {
+        metadata: meta(9, secs),
+        body: DirectListingWithCapitalRaise {
+            stock: stock("TEST"),
+            eligibility: true,
+            min_price: price(8_0000),
+            max_price: price(36_0000),
+            near_exec_price: price(near),
+            near_exec_time: time(secs),
+            lower_collar: price(near / 10 * 9),
+            upper_collar: price(near / 10 * 11),
+        },
+    };
+
+    assert!(tracker.update(&dlcr(100, 20_0000)).is_some());
+    tracker.update(&dlcr(200, 22_0000));
+    tracker.update(&trading_action(
+        9, 300, TradingState::QuoteOnly, TradingActionReason::IpoSecurityReleased
+    ));
+
+    let issue = tracker.issue(9).unwrap();
+    assert!(issue.is_dlcr());
+    assert_eq!(issue.status, IssueStatus::Released);
+    assert_eq!(issue.price_limits(), Some((price(8_0000), price(36_0000))));
+    // Collars follow the latest price discovery update.
+    assert_eq!(issue.collars(), Some((price(19_8000), price(24_2000))));
+
+    // A cross that executes nothing is not the first print.
+    assert!(tracker.update(&cross(9, 400, 0, 22_0000, CrossType::Halt)).is_none());
+    assert_eq!(tracker.issue(9).unwrap().first_print_at(), None);
+
+    tracker.update(&cross(9, 500, 50_000, 22_1000, CrossType::Halt));
+    let issue = tracker.issue(9).unwrap();
+    assert_eq!(issue.status, IssueStatus::Opened);
+    assert_eq!(issue.first_print_at(), Some(time(500)));
+    assert_eq!(issue.timeline.len(), 4);
+}
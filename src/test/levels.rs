@@ -0,0 +1,48 @@
+
+use crate::book::{ BookView, LevelDelta, LevelTracker };
+use crate::msg::Side;
+use super::helpers::*;
+
+fn delta(side: Side, px: u32, size: u64, orders: u32) -> LevelDelta {
+    LevelDelta { stock_locate: 1, side, price: price(px), size, orders }
+}
+
+#[test]
+fn emits_level_deltas() {
+
+    let mut levels = LevelTracker::new().owned();
+
+    let update = levels.update(&add(1, 1, Side::Buy, 100, 10_0000)).unwrap();
+    assert_eq!(update.stock, stock("TEST"));
+    assert_eq!(update.levels.iter().collect::<Vec<_>>(), [&delta(Side::Buy, 10_0000, 100, 1)]);
+
+    levels.update(&add(1, 2, Side::Buy, 50, 10_0000));
+    let update = levels.update(&canceled(1, 1, 30)).unwrap();
+    assert_eq!(update.levels.iter().collect::<Vec<_>>(), [&delta(Side::Buy, 10_0000, 120, 2)]);
+
+    levels.update(&deleted(1, 2));
+    let update = levels.update(&executed(1, 1, 70)).unwrap();
+    let removed = update.levels.iter().next().unwrap();
+    assert!(removed.is_removal());
+    assert_eq!(removed.price, price(10_0000));
+
+    assert!(levels.update(&deleted(1, 99)).is_none());
+}
+
+#[test]
+fn replace_yields_delete_and_add_in_one_update() {
+
+    let mut levels = LevelTracker::new().owned();
+    levels.update(&add(1, 1, Side::Sell, 100, 10_0000));
+    levels.update(&add(1, 2, Side::Sell, 100, 10_0000));
+
+    let update = levels.update(&replaced(1, 1, 3, 60, 10_0100)).unwrap();
+    assert_eq!(update.levels.iter().collect::<Vec<_>>(), [
+        &delta(Side::Sell, 10_0000, 100, 1),
+        &delta(Side::Sell, 10_0100, 60, 1),
+    ]);
+
+    // At the same price only the final state of the level is reported.
+    let update = levels.update(&replaced(1, 3, 4, 40, 10_0100)).unwrap();
+    assert_eq!(update.levels.iter().collect::<Vec<_>>(), [&delta(Side::Sell, 10_0100, 40, 1)]);
+}
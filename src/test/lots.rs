@@ -0,0 +1,71 @@
+
+use crate::book::{ BookView, LotBook, LotClass, LotVolume, PriceLevel };
+use crate::msg::*;
+use super::helpers::*;
+
+fn listed(locate: u16, round_lot_size: u32) -> ItchMessage {
+    let mut msg = directory(locate, "TEST");
+    if let ItchMessage::StockDirectory { body, .. } = &mut msg {
+        body.round_lot_size = round_lot_size;
+    }
+    msg
+}
+
+fn level(px: u32, size: u64, orders: u32) -> Option<PriceLevel> {
+    Some(PriceLevel { price: price(px), size, orders })
+}
+
+fn volume(count: u64, shares: u64) -> LotVolume {
+    LotVolume { count, shares }
+}
+
+#[test]
+fn classifies_quantities() {
+
+    assert_eq!(LotClass::of(99, 100), LotClass::Odd);
+    assert_eq!(LotClass::of(100, 100), LotClass::Round);
+    assert_eq!(LotClass::of(300, 100), LotClass::Round);
+    assert_eq!(LotClass::of(150, 100), LotClass::Mixed);
+
+    let mut book = LotBook::new().owned();
+    book.update(&listed(2, 10));
+    assert_eq!(book.round_lot(1), 100);
+    assert_eq!(book.round_lot(2), 10);
+    assert_eq!(book.classify(1, 25), LotClass::Odd);
+    assert_eq!(book.classify(2, 25), LotClass::Mixed);
+}
+
+#[test]
+fn keeps_odd_lot_depth_apart() {
+
+    let mut book = LotBook::new().owned();
+    book.update(&add(1, 1, Side::Buy, 150, 10_0000));
+    book.update(&add(1, 2, Side::Buy, 50, 10_0000));
+    book.update(&add(1, 3, Side::Sell, 200, 10_1000));
+
+    let round = book.round_lots(1).unwrap();
+    assert_eq!((round.best_bid(), round.best_ask()), (level(10_0000, 150, 1), level(10_1000, 200, 1)));
+    assert_eq!(book.odd_lots(1).unwrap().best_bid(), level(10_0000, 50, 1));
+
+    // The remainder of a partially executed mixed lot is odd lot depth.
+    book.update(&executed(1, 1, 100));
+    assert_eq!(book.round_lots(1).unwrap().best_bid(), None);
+    assert_eq!(book.odd_lots(1).unwrap().best_bid(), level(10_0000, 100, 2));
+
+    // Replacing up to a round lot moves it back.
+    book.update(&replaced(1, 2, 4, 100, 10_0000));
+    assert_eq!(book.round_lots(1).unwrap().best_bid(), level(10_0000, 100, 1));
+    assert_eq!(book.odd_lots(1).unwrap().best_bid(), level(10_0000, 50, 1));
+
+    book.update(&deleted(1, 1));
+    book.update(&executed(1, 3, 30));
+    assert!(book.odd_lots(1).unwrap().is_empty());
+
+    let added = book.added(1);
+    assert_eq!((added.odd, added.round, added.mixed), (volume(1, 50), volume(2, 300), volume(1, 150)));
+    assert_eq!(added.total(), volume(4, 500));
+
+    let executed = book.executed(1);
+    assert_eq!((executed.odd, executed.round, executed.mixed), (volume(1, 30), volume(1, 100), volume(0, 0)));
+    assert_eq!(book.executed(2), Default::default());
+}
@@ -0,0 +1,147 @@
+
+use crate::book::{ BookView, OwnedView };
+use crate::msg::*;
+use crate::track::{ Bands, LimitSide, LuldEventKind, LuldTracker };
+use crate::track::luld::band_width;
+use super::helpers::*;
+
+const SEC: u64 = 1_000_000_000;
+const CLOSE: u64 = 16 * 3600 * SEC;
+const H0950: u32 = 9 * 3600 + 50 * 60;
+
+fn listed(locate: u16, tier: LuldTier, leverage: Option<u32>) -> ItchMessage {
+    let mut msg = directory(locate, "TEST");
+    if let ItchMessage::StockDirectory { body, .. } = &mut msg {
+        body.luld_tier = tier;
+        body.etp_flag = Some(leverage.is_some());
+        body.etp_leverage_factor = leverage.unwrap_or(0);
+    }
+    msg
+}
+
+// Move an order message into market hours.
+fn at(secs: u32, mut msg: ItchMessage) -> ItchMessage {
+    match &mut msg {
+        ItchMessage::OrderAdded { metadata, .. } 
+        | ItchMessage::OrderDeleted { metadata, .. } => *metadata = meta(metadata.stock_locate, secs),
+        _ => unreachable!(),
+    }
+    msg
+}
+
+fn bands(reference: u32, lower: u32, upper: u32) -> LuldEventKind {
+    LuldEventKind::BandsChanged(Bands { 
+        reference: price(reference), 
+        lower: price(lower), 
+        upper: price(upper),
+    })
+}
+
+fn kinds(luld: &mut OwnedView<LuldTracker>, msg: &ItchMessage) -> Vec<LuldEventKind> {
+    luld.update(msg).into_iter().map(|e| e.kind).collect()
+}
+
+#[test]
+fn band_parameters() {
+
+    let at = |h: u64, m: u64| (h * 3600 + m * 60) * SEC;
+
+    // Tier 1 above $3: 5%, doubled for the first 15 minutes.
+    assert_eq!(band_width(LuldTier::Tier1, 1, 10_0000, at(10, 0), CLOSE), Some(5000));
+    assert_eq!(band_width(LuldTier::Tier1, 1, 10_0000, at(9, 35), CLOSE), Some(10000));
+    // Leveraged ETPs multiply the percentage.
+    assert_eq!(band_width(LuldTier::Tier1, 3, 10_0000, at(10, 0), CLOSE), Some(15000));
+    assert_eq!(band_width(LuldTier::Tier2, 3, 10_0000, at(10, 0), CLOSE), Some(10000));
+    // 20% from $0.75 to $3, doubled near the close only at this price.
+    assert_eq!(band_width(LuldTier::Tier2, 1, 2_0000, at(10, 0), CLOSE), Some(4000));
+    assert_eq!(band_width(LuldTier::Tier2, 1, 2_0000, at(15, 40), CLOSE), Some(8000));
+    assert_eq!(band_width(LuldTier::Tier2, 1, 10_0000, at(15, 40), CLOSE), Some(10000));
+    // On early-close days, near that close instead.
+    assert_eq!(band_width(LuldTier::Tier2, 1, 2_0000, at(12, 40), at(13, 0)), Some(8000));
+    assert_eq!(band_width(LuldTier::Tier2, 1, 2_0000, at(15, 40), at(13, 0)), Some(4000));
+    // Lesser of $0.15 or 75% below $0.75.
+    assert_eq!(band_width(LuldTier::Tier1, 1, 5000, at(10, 0), CLOSE), Some(1500));
+    assert_eq!(band_width(LuldTier::Tier1, 1, 1000, at(10, 0), CLOSE), Some(750));
+    assert_eq!(band_width(LuldTier::NotAvailable, 1, 10_0000, at(10, 0), CLOSE), None);
+}
+
+#[test]
+fn flags_limit_state_before_pause() {
+
+    let mut luld = LuldTracker::new().owned();
+    luld.update(&listed(1, LuldTier::Tier1, None));
+
+    // No bands outside market hours.
+    assert!(kinds(&mut luld, &trade(1, 9 * 3600, 10_0000)).is_empty());
+    assert_eq!(luld.bands(1), None);
+
+    let first = kinds(&mut luld, &trade(1, H0950, 10_0000));
+    assert_eq!(first, [bands(10_0000, 9_5000, 10_5000)]);
+
+    // Trades at a band don't make a limit state; the quote does.
+    assert!(kinds(&mut luld, &trade(1, H0950 + 1, 10_5000)).is_empty());
+    luld.update(&at(H0950 + 1, add(1, 1, Side::Sell, 100, 10_6000)));
+    let bid = at(H0950 + 1, add(1, 2, Side::Buy, 100, 10_5000));
+    assert_eq!(kinds(&mut luld, &bid), [LuldEventKind::LimitState(LimitSide::Up)]);
+    assert_eq!(luld.limit_state(1), Some(LimitSide::Up));
+    assert_eq!(kinds(&mut luld, &at(H0950 + 2, deleted(1, 2))), [LuldEventKind::LimitCleared]);
+    let offer = at(H0950 + 3, add(1, 3, Side::Sell, 100, 9_5000));
+    assert_eq!(kinds(&mut luld, &offer), [LuldEventKind::LimitState(LimitSide::Down)]);
+
+    let pause = trading_action(1, H0950 + 4, TradingState::Paused, TradingActionReason::VolatilityPause);
+    assert_eq!(kinds(&mut luld, &pause), [LuldEventKind::Paused { predicted: true }]);
+
+    // Trades during the pause are ignored; the halt cross sets a new reference.
+    assert!(kinds(&mut luld, &trade(1, H0950 + 5, 8_0000)).is_empty());
+    let reopen = kinds(&mut luld, &cross(1, H0950 + 300, 1000, 9_0000, CrossType::Halt));
+    assert_eq!(reopen, [bands(9_0000, 8_5500, 9_4500)]);
+    assert_eq!(luld.limit_state(1), None);
+
+    // Other securities are unaffected.
+    assert_eq!(luld.bands(2), None);
+}
+
+#[test]
+fn reference_follows_average_price() {
+
+    let mut luld = LuldTracker::new().owned();
+    luld.update(&listed(1, LuldTier::Tier2, None));
+    luld.update(&listed(2, LuldTier::Tier1, Some(2)));
+
+    luld.update(&trade(1, H0950, 10_0000));
+    luld.update(&trade(1, H0950 + 10, 10_0500));
+    // A mean within 1% of the reference doesn't move it.
+    assert!(kinds(&mut luld, &trade(1, H0950 + 30, 10_0500)).is_empty());
+
+    luld.update(&trade(1, H0950 + 40, 10_3000));
+    luld.update(&trade(1, H0950 + 50, 10_3000));
+    // Mean of (10.00, 10.05, 10.05, 10.30, 10.30, 10.30) = 10.1667, bands 10%.
+    let moved = kinds(&mut luld, &trade(1, H0950 + 60, 10_3000));
+    assert_eq!(moved, [bands(10_1666, 9_1500, 11_1800)]);
+
+    // 2x leveraged Tier 1 ETP: 10% bands.
+    assert_eq!(kinds(&mut luld, &trade(2, H0950, 20_0000)), [bands(20_0000, 18_0000, 22_0000)]);
+}
+
+#[test]
+fn bands_end_at_an_early_close() {
+
+    let mut luld = LuldTracker::new().close_at(13 * 3600 * SEC).owned();
+    luld.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    luld.update(&system_event(4 * 3600, SystemEvent::BeginSystemHours));
+    luld.update(&system_event(34_200, SystemEvent::BeginMarketHours));
+    luld.update(&listed(1, LuldTier::Tier2, None));
+
+    // 20% at $2.00, doubled from 12:35.
+    assert_eq!(kinds(&mut luld, &trade(1, 12 * 3600, 2_0000)), [bands(2_0000, 1_6000, 2_4000)]);
+    assert_eq!(kinds(&mut luld, &trade(1, 12 * 3600 + 40 * 60, 2_0000)), 
+        [bands(2_0000, 1_2000, 2_8000)]);
+
+    // No bands or limit states once the market has closed.
+    luld.update(&system_event(13 * 3600, SystemEvent::EndMarketHours));
+    assert!(kinds(&mut luld, &trade(1, 13 * 3600 + 60, 2_0000)).is_empty());
+    assert_eq!(luld.bands(1), None);
+    let bid = at(13 * 3600 + 120, add(1, 1, Side::Buy, 100, 3_0000));
+    assert!(kinds(&mut luld, &bid).is_empty());
+    assert_eq!(luld.limit_state(1), None);
+}
@@ -0,0 +1,37 @@
+
+use std::cmp::Ordering;
+use std::time::Duration;
+use crate::msg::*;
+use super::helpers::*;
+
+#[test]
+fn parses_raw_nanos() {
+
+    let nanos: u64 = 34_200_000_000_123; // 09:30:00.000000123
+    let mut bytes = vec![b'S', 0, 1, 0, 2];
+    bytes.extend(&nanos.to_be_bytes()[2..]);
+    bytes.push(b'Q');
+
+    let (rest, msg) = ItchMessage::parse(&bytes).unwrap();
+    assert!(rest.is_empty());
+
+    let meta = msg.metadata();
+    assert_eq!(meta.stock_locate, 1);
+    assert_eq!(meta.tracking_number, 2);
+    assert_eq!(meta.nanos, nanos);
+    assert_eq!(meta.timestamp(), 
+        nsdq_util::NaiveTime::from_hms_nano_opt(9, 30, 0, 123).unwrap());
+}
+
+#[test]
+fn latency_helpers() {
+
+    let first = meta(1, 10);
+    let second = meta(2, 12);
+
+    assert_eq!(second.nanos_since(&first), 2_000_000_000);
+    assert_eq!(first.nanos_since(&second), -2_000_000_000);
+    assert_eq!(second.duration_since(&first), Duration::from_secs(2));
+    assert_eq!(first.duration_since(&second), Duration::ZERO);
+    assert_eq!(first.cmp_time(&second), Ordering::Less);
+}
@@ -0,0 +1,29 @@
+
+mod helpers;
+
+mod montage;
+mod view;
+mod ipo;
+mod clock;
+mod session;
+mod metadata;
+mod frame;
+#[cfg(feature = "async")] mod stream;
+#[cfg(target_os = "linux")] mod feed;
+mod queue;
+mod shard;
+mod depth;
+mod shm;
+mod quote;
+mod levels;
+mod check;
+mod table;
+mod lots;
+mod luld;
+mod short_sale;
+mod rpi;
+mod archive;
+mod file;
+mod split;
+#[cfg(feature = "parallel")] mod parallel;
+#[cfg(feature = "arrow")] mod export;
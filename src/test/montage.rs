@@ -0,0 +1,59 @@
+
+use crate::book::{ BookView, Montage, Level, unattributed };
+use crate::msg::Side;
+use super::helpers::*;
+
+#[test]
+fn attributed_quotes() {
+
+    let mut montage = Montage::new().owned();
+    montage.update(&add_mpid(1, 1, Side::Buy, 100, 10_0000, "ABCD"));
+    montage.update(&add_mpid(1, 2, Side::Buy, 200, 10_0100, "ABCD"));
+    montage.update(&add_mpid(1, 3, Side::Sell, 300, 10_0500, "ABCD"));
+    montage.update(&add_mpid(1, 4, Side::Sell, 50, 10_0400, "WXYZ"));
+
+    let quote = montage.quote(1, mpid("ABCD")).unwrap();
+    assert_eq!(quote.bid, Some(Level { price: price(10_0100), size: 200 }));
+    assert_eq!(quote.ask, Some(Level { price: price(10_0500), size: 300 }));
+
+    let quote = montage.quote(1, mpid("WXYZ")).unwrap();
+    assert_eq!(quote.bid, None);
+    assert_eq!(quote.ask, Some(Level { price: price(10_0400), size: 50 }));
+
+    // Best bid falls back once the top level is executed away.
+    montage.update(&executed(1, 2, 200));
+    let quote = montage.quote(1, mpid("ABCD")).unwrap();
+    assert_eq!(quote.bid, Some(Level { price: price(10_0000), size: 100 }));
+
+    assert_eq!(montage.quotes(1).len(), 2);
+    assert!(montage.quotes(2).is_empty());
+}
+
+#[test]
+fn unattributed_rolls_into_nsdq() {
+
+    let mut montage = Montage::new().owned();
+    montage.update(&add(1, 1, Side::Buy, 100, 10_0000));
+    montage.update(&add(1, 2, Side::Buy, 100, 10_0000));
+    montage.update(&canceled(1, 1, 40));
+
+    let quote = montage.quote(1, unattributed()).unwrap();
+    assert_eq!(quote.bid, Some(Level { price: price(10_0000), size: 160 }));
+}
+
+#[test]
+fn replace_keeps_attribution() {
+
+    let mut montage = Montage::new().owned();
+    montage.update(&add_mpid(1, 1, Side::Sell, 100, 10_0000, "ABCD"));
+    montage.update(&replaced(1, 1, 2, 500, 9_9900));
+
+    let quote = montage.quote(1, mpid("ABCD")).unwrap();
+    assert_eq!(quote.ask, Some(Level { price: price(9_9900), size: 500 }));
+    assert_eq!(montage.book().orders().get(2).unwrap().mpid, mpid("ABCD"));
+    assert!(montage.book().orders().get(1).is_none());
+
+    montage.update(&deleted(1, 2));
+    assert!(montage.quote(1, mpid("ABCD")).is_none());
+    assert!(montage.book().orders().is_empty());
+}
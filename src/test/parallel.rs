@@ -0,0 +1,94 @@
+
+use std::collections::HashMap;
+
+use crate::file::ItchFile;
+use crate::msg::*;
+use super::helpers::*;
+
+const SEC: u64 = 1_000_000_000;
+
+fn write_file(name: &str, msgs: &[Vec<u8>]) -> std::path::PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("litch-{name}-{}.itch", std::process::id()));
+    std::fs::write(&path, raw_file(msgs)).unwrap();
+    path
+}
+
+/// Orders for three locates, with the reference number in file order.
+fn day() -> Vec<Vec<u8>> {
+    (0..10_000u64)
+        .map(|i| raw_add((i % 3) as u16 + 1, (34_200 + i) * SEC, i, 10_0000))
+        .collect()
+}
+
+fn order_ref(msg: &ItchMessage) -> u64 {
+    match msg {
+        ItchMessage::OrderAdded { body, .. } => body.order_ref_num,
+        other => panic!("Unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn chunks_cover_file_at_message_boundaries() {
+
+    let path = write_file("chunks", &day());
+    let file = ItchFile::open(&path).unwrap();
+
+    let chunks = file.chunks(7);
+    assert_eq!(chunks.len(), 7);
+    assert_eq!(chunks.iter().map(|c| c.bytes.len()).sum::<usize>(), file.bytes().len());
+    for pair in chunks.windows(2) {
+        assert_eq!(pair[0].offset + pair[0].bytes.len(), pair[1].offset);
+    }
+    let total: usize = chunks.iter().map(|c| c.parse().unwrap().len()).sum();
+    assert_eq!(total, 10_000);
+
+    let indexed = file.chunks_indexed(&file.build_index(100), 8);
+    assert_eq!(indexed.len(), 8);
+    let total: usize = indexed.iter().map(|c| c.parse().unwrap().len()).sum();
+    assert_eq!(total, 10_000);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn aggregates_per_locate_in_parallel() {
+
+    let path = write_file("aggregate", &day());
+    let file = ItchFile::open(&path).unwrap();
+    let chunks = file.chunks(16);
+
+    let count = file.par_fold(&chunks, || 0u64, |n, _| *n += 1, |a, b| a + b).unwrap();
+    assert_eq!(count, 10_000);
+
+    // Per-locate reference numbers, which must stay in file order.
+    let refs: HashMap<u16, Vec<u64>> = file.par_by_locate(
+        &chunks, 
+        |refs: &mut Vec<u64>, msg| refs.push(order_ref(msg)), 
+        |refs, later| refs.extend(later),
+    ).unwrap();
+
+    assert_eq!(refs.len(), 3);
+    assert_eq!(refs[&2], (0..10_000).filter(|i| i % 3 == 1).collect::<Vec<_>>());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn iterates_in_order_and_stops_at_error() {
+
+    let mut msgs = day();
+    msgs[7_000][0] = b'?';
+    let path = write_file("ordered", &msgs);
+    let file = ItchFile::open(&path).unwrap();
+    let chunks = file.chunks(16);
+
+    let mut iter = file.par_messages(&chunks, 4);
+    for i in 0..7_000 {
+        assert_eq!(order_ref(&iter.next().unwrap().unwrap()), i);
+    }
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,59 @@
+
+use std::thread;
+use crate::queue::{ channel, Envelope, Overflow };
+use super::helpers::*;
+
+fn envelope(sequence: u64) -> Envelope {
+    Envelope { sequence, message: deleted(1, sequence) }
+}
+
+#[test]
+fn overflow_policies() {
+
+    let (mut tx, mut rx) = channel(4, Overflow::DropNewest);
+    for i in 0..6 { let _ = tx.push(envelope(i)); }
+    assert_eq!(tx.dropped(), 2);
+    let seqs: Vec<_> = std::iter::from_fn(|| rx.try_pop()).map(|e| e.sequence).collect();
+    assert_eq!(seqs, vec![0, 1, 2, 3]);
+
+    let (mut tx, mut rx) = channel(4, Overflow::DropOldest);
+    for i in 0..6 { tx.push(envelope(i)).unwrap(); }
+    assert_eq!(rx.dropped(), 2);
+    assert_eq!(rx.len(), 4);
+    let seqs: Vec<_> = std::iter::from_fn(|| rx.try_pop()).map(|e| e.sequence).collect();
+    assert_eq!(seqs, vec![2, 3, 4, 5]);
+
+    // A vanished consumer never blocks the producer.
+    let (mut tx, rx) = channel(2, Overflow::Block);
+    drop(rx);
+    assert!(tx.push(envelope(0)).is_err());
+}
+
+fn threaded(overflow: Overflow) -> (Vec<u64>, u64) {
+
+    const COUNT: u64 = 200_000;
+    let (mut tx, rx) = channel(64, overflow);
+
+    let producer = thread::spawn(move || {
+        for i in 0..COUNT { let _ = tx.push(envelope(i)); }
+    });
+
+    let received: Vec<u64> = rx.map(|e| e.sequence).collect();
+    producer.join().unwrap();
+
+    assert!(received.windows(2).all(|w| w[0] < w[1]));
+    (received, COUNT)
+}
+
+#[test]
+fn threaded_block_loses_nothing() {
+    let (received, count) = threaded(Overflow::Block);
+    assert_eq!(received.len() as u64, count);
+}
+
+#[test]
+fn threaded_drop_oldest_keeps_order() {
+    let (received, _) = threaded(Overflow::DropOldest);
+    // The final value is never dropped.
+    assert_eq!(received.last(), Some(&199_999));
+}
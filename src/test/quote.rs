@@ -0,0 +1,55 @@
+
+use crate::book::{ BookView, Level, QuoteTracker };
+use crate::msg::Side;
+use super::helpers::*;
+
+fn level(px: u32, size: u64) -> Option<Level> {
+    Some(Level { price: price(px), size })
+}
+
+#[test]
+fn emits_only_bbo_changes() {
+
+    let mut quotes = QuoteTracker::new().owned();
+
+    let first = quotes.update(&add(1, 1, Side::Buy, 100, 10_0000)).unwrap();
+    assert_eq!(first.stock, stock("TEST"));
+    assert_eq!((first.bid, first.ask), (level(10_0000, 100), None));
+
+    let update = quotes.update(&add(1, 2, Side::Sell, 200, 10_0100)).unwrap();
+    assert_eq!((update.bid, update.ask), (level(10_0000, 100), level(10_0100, 200)));
+
+    // Deeper levels and cancels below the top don't change the quote.
+    assert!(quotes.update(&add(1, 3, Side::Buy, 50, 9_9900)).is_none());
+    assert!(quotes.update(&canceled(1, 3, 20)).is_none());
+    assert!(quotes.update(&deleted(1, 3)).is_none());
+
+    // Size changes at the top do.
+    let update = quotes.update(&executed(1, 1, 40)).unwrap();
+    assert_eq!(update.bid, level(10_0000, 60));
+
+    // Unknown orders and other messages change nothing.
+    assert!(quotes.update(&deleted(1, 99)).is_none());
+    assert!(quotes.update(&system_event(5, crate::msg::SystemEvent::BeginMarketHours)).is_none());
+}
+
+#[test]
+fn coalesces_replace_into_one_update() {
+
+    let mut quotes = QuoteTracker::new().owned();
+    quotes.update(&add(1, 1, Side::Buy, 100, 10_0000));
+    quotes.update(&add(2, 2, Side::Buy, 100, 20_0000));
+
+    // Remove and re-add at a better price: one update with the final state.
+    let update = quotes.update(&replaced(1, 1, 3, 80, 10_0500)).unwrap();
+    assert_eq!(update.stock_locate, 1);
+    assert_eq!(update.bid, level(10_0500, 80));
+
+    // Replacing with identical price and size is a no-op for the quote.
+    assert!(quotes.update(&replaced(1, 3, 4, 80, 10_0500)).is_none());
+
+    // Removing the last bid empties that side.
+    let update = quotes.update(&deleted(1, 4)).unwrap();
+    assert_eq!((update.bid, update.ask), (None, None));
+    assert_eq!(quotes.quote(2), (level(20_0000, 100), None));
+}
@@ -0,0 +1,69 @@
+
+use std::time::Duration;
+
+use crate::msg::*;
+use crate::track::{ RpiTracker, RpiUpdate };
+use super::helpers::*;
+
+fn rpi(locate: u16, secs: u32, interest_flag: InterestFlag) -> ItchMessage {
+    ItchMessage::RetailPriceImprovement {
+        metadata: meta(locate, secs),
+        body: RetailPriceImprovement { stock: stock("TEST"), interest_flag },
+    }
+}
+
+#[test]
+fn records_interest_intervals() {
+
+    use InterestFlag::*;
+
+    let mut tracker = RpiTracker::new();
+    let Some(RpiUpdate::Interest(event)) = tracker.update(&rpi(1, 100, BuyAvailable)) else { 
+        panic!("Expected an interest change") 
+    };
+    assert_eq!((event.previous, event.interest), (NoneAvailable, BuyAvailable));
+
+    // Repeated flags are not changes.
+    assert!(tracker.update(&rpi(1, 150, BuyAvailable)).is_none());
+    tracker.update(&rpi(1, 200, AnyAvailable));
+    tracker.update(&rpi(1, 300, SellAvailable));
+    tracker.update(&rpi(1, 400, NoneAvailable));
+    tracker.update(&rpi(1, 500, SellAvailable));
+    tracker.update(&rpi(2, 100, AnyAvailable));
+
+    assert_eq!(tracker.interest(1), SellAvailable);
+    assert_eq!(tracker.interest(3), NoneAvailable);
+
+    let history = tracker.history(1).unwrap();
+    assert_eq!(history.intervals.len(), 3);
+    assert_eq!(history.intervals[0].side, Side::Buy);
+    assert_eq!((history.intervals[0].start, history.intervals[0].end), (time(100), Some(time(300))));
+    assert_eq!(history.intervals[2].end, None);
+    assert_eq!(history.interest_at(time(250)), (true, true));
+    assert_eq!(history.interest_at(time(450)), (false, false));
+
+    let secs = Duration::from_secs;
+    let summaries = tracker.summaries(time(600));
+    assert_eq!(summaries.len(), 2);
+    let first = summaries[0];
+    assert_eq!((first.buy, first.sell, first.both, first.either), (secs(200), secs(300), secs(100), secs(400)));
+    assert_eq!(first.changes, 5);
+    assert_eq!((summaries[1].both, summaries[1].either), (secs(500), secs(500)));
+
+    // The day's summaries are reported when it ends.
+    let end = tracker.update(&system_event(600, SystemEvent::EndMessages));
+    assert_eq!(end, Some(RpiUpdate::Day(summaries)));
+
+    // A new day starts a new timeline.
+    assert!(tracker.update(&system_event(0, SystemEvent::BeginMessages)).is_none());
+    assert!(tracker.summaries(time(600)).is_empty());
+
+    // Without `EndMessages`, the day is reported at the next `BeginMessages`.
+    tracker.update(&rpi(1, 100, AnyAvailable));
+    tracker.update(&rpi(1, 200, NoneAvailable));
+    let Some(RpiUpdate::Day(day)) = tracker.update(&system_event(0, SystemEvent::BeginMessages)) else { 
+        panic!("Expected the day's summaries") 
+    };
+    assert_eq!((day.len(), day[0].both), (1, secs(100)));
+    assert!(tracker.history(1).is_none());
+}
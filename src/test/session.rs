@@ -0,0 +1,94 @@
+
+use std::sync::{ Arc, Mutex };
+
+use crate::msg::{ Side, SystemEvent::* };
+use crate::session::{ Phase, Session, SessionIssueKind };
+use super::helpers::*;
+
+#[test]
+fn follows_the_day() {
+
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let seen = transitions.clone();
+    let mut session = Session::new()
+        .on_transition(move |t| seen.lock().unwrap().push((t.from, t.to)));
+
+    assert_eq!(session.phase(), Phase::NotStarted);
+    assert_eq!(session.update(&system_event(1, BeginMessages)), Phase::StartOfMessages);
+    assert_eq!(session.tag(add(1, 1, Side::Buy, 100, 10_0000)).phase, Phase::StartOfMessages);
+    session.update(&system_event(2, BeginSystemHours));
+    session.update(&system_event(3, BeginMarketHours));
+    assert_eq!(session.update(&add(1, 2, Side::Buy, 100, 10_0000)), Phase::MarketHours);
+    session.update(&system_event(4, EndMarketHours));
+    session.update(&system_event(5, EndSystemHours));
+
+    // Deletes (and broken trades) are documented after the end of system hours.
+    assert_eq!(session.update(&deleted(1, 1)), Phase::Closed);
+    assert!(session.issues().is_empty());
+
+    // Anything else is flagged, once per phase.
+    session.update(&add(1, 3, Side::Buy, 100, 10_0000));
+    session.update(&add(1, 4, Side::Buy, 100, 10_0000));
+    assert_eq!(session.issues().len(), 1);
+    assert_eq!(session.issues()[0].kind, SessionIssueKind::Unexpected { 
+        kind: "OrderAdded", 
+        phase: Phase::Closed,
+    });
+
+    session.update(&system_event(6, EndMessages));
+    // Next day.
+    assert_eq!(session.update(&system_event(7, BeginMessages)), Phase::StartOfMessages);
+    assert_eq!(session.issues().len(), 1);
+
+    let transitions = transitions.lock().unwrap();
+    assert_eq!(transitions.len(), 7);
+    assert_eq!(transitions[0], (Phase::NotStarted, Phase::StartOfMessages));
+    assert_eq!(transitions[6], (Phase::EndOfMessages, Phase::StartOfMessages));
+}
+
+#[test]
+fn flags_missing_and_out_of_order_events() {
+
+    let issues = Arc::new(Mutex::new(0));
+    let count = issues.clone();
+    let mut session = Session::new().on_issue(move |_| *count.lock().unwrap() += 1);
+
+    session.update(&add(1, 1, Side::Buy, 100, 10_0000));
+    session.update(&system_event(1, BeginMessages));
+    session.update(&system_event(2, BeginMarketHours));
+    session.update(&system_event(3, BeginSystemHours));
+    assert_eq!(session.phase(), Phase::MarketHours);
+
+    let kinds: Vec<_> = session.issues().iter().map(|i| i.kind).collect();
+    assert_eq!(kinds, [
+        SessionIssueKind::Unexpected { kind: "OrderAdded", phase: Phase::NotStarted },
+        SessionIssueKind::Missing(BeginSystemHours),
+        SessionIssueKind::OutOfOrder { event: BeginSystemHours, phase: Phase::MarketHours },
+    ]);
+    assert_eq!(*issues.lock().unwrap(), 3);
+}
+
+#[test]
+fn starts_next_day_without_end_of_messages() {
+
+    let mut session = Session::new();
+    session.update(&system_event(1, BeginMessages));
+    session.update(&system_event(2, BeginSystemHours));
+    session.update(&system_event(3, BeginMarketHours));
+    session.update(&system_event(4, EndMarketHours));
+    session.update(&system_event(5, EndSystemHours));
+
+    // `EndMessages` was lost: the next day still starts.
+    assert_eq!(session.update(&system_event(6, BeginMessages)), Phase::StartOfMessages);
+    assert_eq!(session.update(&add(1, 1, Side::Buy, 100, 10_0000)), Phase::StartOfMessages);
+
+    let kinds: Vec<_> = session.issues().iter().map(|i| i.kind).collect();
+    assert_eq!(kinds, [SessionIssueKind::Missing(EndMessages)]);
+
+    // A repeated `BeginMessages` is not a new day.
+    session.update(&system_event(7, BeginMessages));
+    assert_eq!(session.issues()[1].kind, SessionIssueKind::OutOfOrder { 
+        event: BeginMessages, 
+        phase: Phase::StartOfMessages,
+    });
+}
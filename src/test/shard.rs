@@ -0,0 +1,92 @@
+
+use crate::Pipeline;
+use crate::msg::{ ItchMessage, SystemEvent };
+use crate::queue::Envelope;
+use super::helpers::*;
+
+struct Recorder(Vec<Envelope>);
+
+impl crate::Shard for Recorder {
+    fn handle(&mut self, envelope: Envelope) { self.0.push(envelope) }
+}
+
+#[test]
+fn routes_by_locate_and_broadcasts() {
+
+    let mut pipeline = Pipeline::new(3, 16, |_| Recorder(vec![]));
+
+    pipeline.send_message(system_event(0, SystemEvent::BeginMessages)).unwrap();
+    for i in 0..1_000u64 {
+        pipeline.send_message(deleted((i % 10) as u16 + 1, i)).unwrap();
+        if i == 500 {
+            pipeline.send_message(system_event(1, SystemEvent::BeginMarketHours)).unwrap();
+        }
+    }
+    pipeline.send_message(system_event(2, SystemEvent::EndMessages)).unwrap();
+
+    let shards: Vec<Vec<Envelope>> = pipeline.finish()
+        .into_iter()
+        .map(|r| r.0)
+        .collect();
+    assert_eq!(shards.len(), 3);
+
+    let mut total = 0;
+    for (index, received) in shards.iter().enumerate() {
+
+        // Ordering is preserved within each shard.
+        let seqs: Vec<u64> = received.iter().map(|e| e.sequence).collect();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+
+        let events: Vec<&Envelope> = received.iter()
+            .filter(|e| e.message.is_market_wide())
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].sequence, 1);
+        assert_eq!(events[1].sequence, 503);
+
+        for env in received.iter().filter(|e| !e.message.is_market_wide()) {
+            assert_eq!(env.message.metadata().stock_locate as usize % 3, index);
+            total += 1;
+        }
+    }
+    assert_eq!(total, 1_000);
+}
+
+#[test]
+fn custom_shard_state() {
+
+    struct Counter(u64);
+    impl crate::Shard for Counter {
+        fn handle(&mut self, envelope: Envelope) {
+            if let ItchMessage::OrderDeleted { .. } = envelope.message { self.0 += 1 }
+        }
+    }
+
+    let mut pipeline = Pipeline::new(2, 4, |_| Counter(0));
+    for i in 0..100 { pipeline.send_message(deleted(i, i as u64)).unwrap(); }
+    let counts: Vec<u64> = pipeline.finish().iter().map(|c| c.0).collect();
+    assert_eq!(counts, vec![50, 50]);
+}
+
+#[test]
+fn reports_stopped_shard() {
+
+    let mut pipeline = Pipeline::new(2, 4, |index| move |_: Envelope| {
+        if index == 1 { panic!("shard failure") }
+    });
+
+    // Keep sending until the failed shard's queue is seen as closed.
+    let stopped = (0..10_000u64)
+        .find_map(|i| pipeline.send_message(deleted(1, i)).err())
+        .expect("Shard 1 stops");
+    assert_eq!(stopped.shard, 1);
+    assert_eq!(stopped.envelope.message.metadata().stock_locate, 1);
+
+    // Other shards still take messages.
+    pipeline.send_message(deleted(2, 0)).unwrap();
+
+    let results = pipeline.try_finish();
+    assert!(results[0].is_ok());
+    let Err(payload) = &results[1] else { panic!("Shard 1 panicked") };
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"shard failure"));
+}
@@ -0,0 +1,52 @@
+
+use crate::book::{ BookView, TopOfBookPublisher, TopOfBookReader };
+use crate::msg::*;
+use super::helpers::*;
+
+#[test]
+fn publishes_top_of_book() {
+
+    let path = std::env::temp_dir().join(format!("litch-bbo-{}.shm", std::process::id()));
+    let mut publisher = TopOfBookPublisher::create(&path, 100).unwrap().owned();
+    let reader = TopOfBookReader::open(&path).unwrap();
+    assert_eq!(reader.len(), 101);
+    assert!(reader.get(5).is_none());
+
+    publisher.update(&add(5, 1, Side::Buy, 100, 10_0000));
+    publisher.update(&add(5, 2, Side::Buy, 200, 10_0000));
+    publisher.update(&add(5, 3, Side::Sell, 300, 10_0200));
+    publisher.update(&executed(5, 1, 40));
+    publisher.update(&trading_action(
+        5, 10, TradingState::Trading, TradingActionReason::ReasonNotAvailable
+    ));
+
+    let top = reader.get(5).unwrap();
+    assert_eq!(top.stock_locate, 5);
+    assert_eq!(top.bid, Some((price(10_0000), 260)));
+    assert_eq!(top.ask, Some((price(10_0200), 300)));
+    assert_eq!(top.last_trade, Some((price(10_0000), 40)));
+    assert_eq!(top.state, Some(TradingState::Trading));
+    assert_eq!(top.nanos, 10_000_000_000);
+
+    // Crosses update the last sale without touching the book.
+    publisher.update(&cross(5, 11, 500, 10_0100, CrossType::Opening));
+    let top = reader.get(5).unwrap();
+    assert_eq!(top.last_trade, Some((price(10_0100), 500)));
+    assert_eq!(top.bid, Some((price(10_0000), 260)));
+
+    // Out-of-range locates are ignored.
+    publisher.update(&add(500, 9, Side::Buy, 1, 1_0000));
+    assert!(reader.get(500).is_none());
+
+    // Recreating the file leaves existing readers with the old records.
+    let _publisher = TopOfBookPublisher::create(&path, 10).unwrap();
+    assert_eq!(reader.get(5).unwrap().bid, Some((price(10_0000), 260)));
+    let reopened = TopOfBookReader::open(&path).unwrap();
+    assert_eq!(reopened.len(), 11);
+    assert!(reopened.get(5).is_none());
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::write(&path, b"garbage").unwrap();
+    assert!(TopOfBookReader::open(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,149 @@
+
+use crate::book::{ BookView, OwnedView };
+use crate::msg::*;
+use crate::track::{ ShortSaleEventKind, ShortSaleMonitor };
+use super::helpers::*;
+
+fn reg_sho(locate: u16, symbol: &str, action: RegShoAction) -> ItchMessage {
+    ItchMessage::RegShoRestriction {
+        metadata: meta(locate, 4 * 3600),
+        body: RegShoRestriction { stock: stock(symbol), action },
+    }
+}
+
+fn kinds(monitor: &mut OwnedView<ShortSaleMonitor>, msg: &ItchMessage) -> Vec<ShortSaleEventKind> {
+    monitor.update(msg).into_iter().map(|e| e.kind).collect()
+}
+
+#[test]
+fn predicts_trigger_and_carries_over() {
+
+    let mut monitor = ShortSaleMonitor::new().owned();
+    monitor.prior_close(stock("TEST"), price(10_0000));
+
+    monitor.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    monitor.update(&directory(1, "TEST"));
+    assert_eq!(
+        kinds(&mut monitor, &reg_sho(1, "TEST", RegShoAction::NoPriceTest)), 
+        [ShortSaleEventKind::Confirmed(RegShoAction::NoPriceTest)]
+    );
+
+    let trigger = price(9_0000);
+    assert!(kinds(&mut monitor, &trade(1, 36_000, 9_5000)).is_empty());
+    assert_eq!(
+        kinds(&mut monitor, &trade(1, 36_001, 9_1900)), 
+        [ShortSaleEventKind::Approaching { price: price(9_1900), trigger }]
+    );
+    // Warnings are only given once.
+    assert!(kinds(&mut monitor, &trade(1, 36_002, 9_1000)).is_empty());
+
+    // Executions are priced from the book.
+    monitor.update(&add(1, 7, Side::Buy, 100, 9_0000));
+    assert_eq!(
+        kinds(&mut monitor, &executed(1, 7, 100)), 
+        [ShortSaleEventKind::Triggered { price: trigger, trigger }]
+    );
+    assert_eq!(
+        kinds(&mut monitor, &reg_sho(1, "TEST", RegShoAction::PriceDrop)), 
+        [ShortSaleEventKind::Confirmed(RegShoAction::PriceDrop)]
+    );
+
+    monitor.update(&cross(1, 57_600, 5000, 9_2000, CrossType::Closing));
+    let status = monitor.status(&stock("TEST")).unwrap();
+    assert!(status.is_restricted());
+    assert_eq!(status.low, Some(trigger));
+
+    // Next day: new locate, prior close from the closing cross.
+    monitor.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    monitor.update(&directory(5, "TEST"));
+    let status = monitor.status(&stock("TEST")).unwrap();
+    assert!(status.carried_over && !status.triggered);
+    assert_eq!((status.prior_close, status.trigger), (Some(price(9_2000)), Some(price(8_2800))));
+    assert_eq!(
+        kinds(&mut monitor, &reg_sho(5, "TEST", RegShoAction::RemainInEffect)), 
+        [ShortSaleEventKind::Confirmed(RegShoAction::RemainInEffect)]
+    );
+    assert_eq!(monitor.restricted().collect::<Vec<_>>(), [stock("TEST")]);
+
+    // The carry-over lasts a single day.
+    monitor.next_day();
+    assert!(!monitor.status(&stock("TEST")).unwrap().is_restricted());
+}
+
+#[test]
+fn reports_mismatches() {
+
+    let mut monitor = ShortSaleMonitor::new().warn_at(500).owned();
+    monitor.prior_close(stock("TEST"), price(10_0000));
+    monitor.update(&directory(1, "TEST"));
+    monitor.update(&directory(2, "MSFT"));
+
+    // No prior close for MSFT, so the restriction was not predicted.
+    let events = monitor.update(&reg_sho(2, "MSFT", RegShoAction::PriceDrop));
+    assert_eq!(events[0].stock, stock("MSFT"));
+    assert_eq!(events[0].kind, ShortSaleEventKind::Mismatch { 
+        expected: RegShoAction::NoPriceTest, 
+        actual: RegShoAction::PriceDrop,
+    });
+    assert!(monitor.status(&stock("MSFT")).unwrap().is_restricted());
+
+    assert!(matches!(
+        kinds(&mut monitor, &trade(1, 36_000, 9_5000))[..], 
+        [ShortSaleEventKind::Approaching { .. }]
+    ));
+    assert_eq!(
+        kinds(&mut monitor, &reg_sho(1, "TEST", RegShoAction::RemainInEffect)), 
+        [ShortSaleEventKind::Mismatch { 
+            expected: RegShoAction::NoPriceTest, 
+            actual: RegShoAction::RemainInEffect,
+        }]
+    );
+}
+
+#[test]
+fn prior_close_ignores_post_market_trades() {
+
+    let mut monitor = ShortSaleMonitor::new().owned();
+    start_day(&mut monitor);
+    monitor.update(&system_event(34_200, SystemEvent::BeginMarketHours));
+
+    // No closing cross: the last regular-hours sale is the close.
+    monitor.update(&trade(1, 57_599, 10_0000));
+    monitor.update(&system_event(57_600, SystemEvent::EndMarketHours));
+    monitor.update(&trade(1, 60_000, 8_0000));
+
+    monitor.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    let status = monitor.status(&stock("TEST")).unwrap();
+    assert_eq!(status.prior_close, Some(price(10_0000)));
+}
+
+// Start a day with security 1 listed, in pre-market system hours.
+fn start_day(monitor: &mut OwnedView<ShortSaleMonitor>) {
+    monitor.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    monitor.update(&directory(1, "TEST"));
+    monitor.update(&system_event(4 * 3600, SystemEvent::BeginSystemHours));
+}
+
+#[test]
+fn prior_close_follows_market_hours() {
+
+    let mut monitor = ShortSaleMonitor::new().owned();
+    start_day(&mut monitor);
+
+    // A pre-market trade is not a close, even if nothing trades after it.
+    monitor.update(&trade(1, 8 * 3600, 12_0000));
+    monitor.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    assert_eq!(monitor.status(&stock("TEST")).unwrap().prior_close, None);
+
+    // Early close at 13:00: later trades are after hours.
+    monitor.update(&directory(1, "TEST"));
+    monitor.update(&system_event(4 * 3600, SystemEvent::BeginSystemHours));
+    monitor.update(&system_event(34_200, SystemEvent::BeginMarketHours));
+    monitor.update(&trade(1, 12 * 3600, 10_0000));
+    monitor.update(&system_event(13 * 3600, SystemEvent::EndMarketHours));
+    monitor.update(&trade(1, 14 * 3600, 9_0000));
+
+    monitor.update(&system_event(3 * 3600, SystemEvent::BeginMessages));
+    let status = monitor.status(&stock("TEST")).unwrap();
+    assert_eq!(status.prior_close, Some(price(10_0000)));
+}
@@ -0,0 +1,71 @@
+
+use crate::file::{ ItchFile, Splitter };
+use super::helpers::*;
+
+const SEC: u64 = 1_000_000_000;
+
+fn kinds(file: &ItchFile) -> Vec<(&'static str, u16)> {
+    file.messages()
+        .map(|msg| msg.unwrap())
+        .map(|msg| (msg.kind(), msg.metadata().stock_locate))
+        .collect()
+}
+
+#[test]
+fn splits_day_by_symbol() {
+
+    let dir = std::env::temp_dir()
+        .join(format!("litch-split-{}", std::process::id()));
+    let input = dir.with_extension("itch");
+
+    std::fs::write(&input, raw_file(&[
+        raw_system_event(0, SEC, b'O'),
+        raw_directory(1, 2 * SEC, "AAPL"),
+        raw_directory(2, 3 * SEC, "MSFT"),
+        raw_add(1, 4 * SEC, 1, 10_0000),
+        raw_add(2, 5 * SEC, 1, 20_0000),
+        raw_add(3, 5 * SEC, 1, 20_0000),
+        raw_system_event(0, 6 * SEC, b'Q'),
+        raw_deleted(2, 7 * SEC, 1),
+    ])).unwrap();
+
+    let stats = Splitter::new(&dir).unwrap()
+        .buffer_limit(16)
+        .split(&ItchFile::open(&input).unwrap())
+        .unwrap();
+
+    assert_eq!(stats.messages, 8);
+    assert_eq!(stats.routed, 5);
+    assert_eq!(stats.market_wide, 2);
+    assert_eq!(stats.skipped, 1);
+    assert_eq!(stats.files, [dir.join("AAPL.itch"), dir.join("MSFT.itch")]);
+
+    let aapl = ItchFile::open(dir.join("AAPL.itch")).unwrap();
+    assert_eq!(kinds(&aapl), [
+        ("SystemEvent", 0), 
+        ("StockDirectory", 1), 
+        ("OrderAdded", 1), 
+        ("SystemEvent", 0),
+    ]);
+
+    let msft = ItchFile::open(dir.join("MSFT.itch")).unwrap();
+    assert_eq!(kinds(&msft), [
+        ("SystemEvent", 0), 
+        ("StockDirectory", 2), 
+        ("OrderAdded", 2), 
+        ("SystemEvent", 0),
+        ("OrderDeleted", 2),
+    ]);
+
+    // Selecting symbols skips the others' messages.
+    let stats = Splitter::new(&dir).unwrap()
+        .symbols(["AAPL"])
+        .split(&ItchFile::open(&input).unwrap())
+        .unwrap();
+    assert_eq!(stats.files, [dir.join("AAPL.itch")]);
+    assert_eq!(stats.routed, 2);
+    assert_eq!(kinds(&ItchFile::open(dir.join("AAPL.itch")).unwrap()).len(), 4);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_file(&input).unwrap();
+}
@@ -0,0 +1,103 @@
+
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{ TcpListener, TcpStream, UdpSocket };
+
+use crate::{ ItchMessage, ItchStream, TcpFraming };
+use super::helpers::*;
+
+#[tokio::test]
+async fn mold_udp_loopback() {
+
+    let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    sender.connect(receiver.local_addr().unwrap()).await.unwrap();
+
+    let msgs: Vec<_> = (0..4).map(|i| raw_system_event(0, i, b'O')).collect();
+    sender.send(&mold_packet(1, &msgs[..2])).await.unwrap();
+    sender.send(&mold_packet(1, &msgs[..2])).await.unwrap(); // Duplicate
+    sender.send(&mold_packet(5, &msgs[2..])).await.unwrap(); // Gap at 3, 4
+    sender.send(&mold_packet(7, &[])).await.unwrap(); // Heartbeat
+    let mut end = mold_packet(7, &[]);
+    end[18..20].copy_from_slice(&[0xFF, 0xFF]);
+    sender.send(&end).await.unwrap();
+
+    let mut stream = ItchStream::mold_udp(receiver);
+    let mut nanos = vec![];
+    while let Some(msg) = stream.next().await {
+        nanos.push(msg.unwrap().metadata().nanos);
+    }
+
+    assert_eq!(nanos, vec![0, 1, 2, 3]);
+    assert_eq!(stream.sequencer().missed(), 2);
+    assert_eq!(stream.sequencer().duplicates(), 2);
+}
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+    let (server, _) = listener.accept().await.unwrap();
+    (server, client)
+}
+
+#[tokio::test]
+async fn length_prefixed_tcp() {
+
+    let (mut server, client) = tcp_pair().await;
+
+    let mut bytes = vec![];
+    for i in 0..100 {
+        let msg = raw_system_event(0, i, b'Q');
+        bytes.extend((msg.len() as u16).to_be_bytes());
+        bytes.extend(msg);
+    }
+
+    tokio::spawn(async move {
+        // Split writes across message boundaries.
+        for chunk in bytes.chunks(7) {
+            server.write_all(chunk).await.unwrap();
+        }
+    });
+
+    let stream = ItchStream::tcp(client, TcpFraming::LengthPrefixed);
+    let msgs: Vec<ItchMessage> = stream.map(Result::unwrap).collect().await;
+    assert_eq!(msgs.len(), 100);
+    assert_eq!(msgs[99].metadata().nanos, 99);
+}
+
+#[tokio::test]
+async fn soup_bin_tcp() {
+
+    let (mut server, client) = tcp_pair().await;
+
+    let mut bytes = vec![0, 1, b'H'];
+    for i in 0..3 {
+        let msg = raw_system_event(0, i, b'Q');
+        bytes.extend((msg.len() as u16 + 1).to_be_bytes());
+        bytes.push(b'S');
+        bytes.extend(msg);
+    }
+    bytes.extend([0, 1, b'Z']);
+    bytes.extend([0, 1, b'H']); // Nothing is read after end of session.
+
+    server.write_all(&bytes).await.unwrap();
+
+    let stream = ItchStream::tcp(client, TcpFraming::SoupBinTcp);
+    let msgs: Vec<ItchMessage> = stream.map(Result::unwrap).collect().await;
+    assert_eq!(msgs.len(), 3);
+}
+
+#[tokio::test]
+async fn unknown_tag_is_an_error() {
+
+    let (mut server, client) = tcp_pair().await;
+    let mut msg = raw_system_event(0, 0, b'Q');
+    msg[0] = b'!';
+    server.write_all(&(msg.len() as u16).to_be_bytes()).await.unwrap();
+    server.write_all(&msg).await.unwrap();
+    drop(server);
+
+    let mut stream = ItchStream::tcp(client, TcpFraming::LengthPrefixed);
+    assert!(matches!(stream.next().await, Some(Err(crate::Error::Message('!')))));
+    assert!(stream.next().await.is_none());
+}
@@ -0,0 +1,57 @@
+
+use crate::book::{ CompactOrder, Order, OrderTable };
+use crate::msg::Side;
+use super::helpers::*;
+
+fn order(quantity: u32) -> CompactOrder {
+    CompactOrder { price: price(10_0000), quantity, stock_locate: 1, side: Side::Buy }
+}
+
+#[test]
+fn records_are_compact() {
+    assert_eq!(std::mem::size_of::<CompactOrder>(), 12);
+    assert_eq!(std::mem::size_of::<Option<CompactOrder>>(), 12);
+    // The records `Orders` keeps, with attribution.
+    assert_eq!(std::mem::size_of::<Option<Order>>(), 16);
+}
+
+#[test]
+fn stores_and_frees_pages() {
+
+    let mut table = OrderTable::new();
+    for r in 0..10_000 {
+        assert!(table.insert(r, order(100)).is_none());
+    }
+    assert_eq!(table.len(), 10_000);
+    assert_eq!(table.pages(), 3);
+    assert_eq!(table.insert(5, order(50)), Some(order(100)));
+    assert_eq!(table.len(), 10_000);
+
+    assert_eq!(table.reduce(5, 20), Some((order(30), 20)));
+    assert_eq!(table.reduce(5, 40), Some((order(0), 30)));
+    assert!(table.get(5).is_none());
+    assert!(table.reduce(5, 1).is_none());
+
+    // Emptied pages are released, except the one being filled.
+    for r in 0..10_000 {
+        table.remove(r);
+    }
+    assert!(table.is_empty());
+    assert_eq!(table.pages(), 1);
+
+    // Sparse and very large reference numbers.
+    table.insert(1 << 30, order(1));
+    table.insert(u64::MAX, order(2));
+    assert_eq!(table.get(1 << 30), Some(&order(1)));
+    assert_eq!(table.get_mut(u64::MAX).map(|o| o.quantity), Some(2));
+    assert_eq!(table.remove(u64::MAX), Some(order(2)));
+    assert_eq!(table.len(), 1);
+
+    // The edge of the direct range goes to the overflow map.
+    let pages = table.pages();
+    table.insert((1 << 34) - 1, order(3));
+    table.insert(1 << 34, order(4));
+    assert_eq!(table.pages(), pages + 1);
+    assert_eq!(table.get(1 << 34), Some(&order(4)));
+    assert_eq!(table.remove((1 << 34) - 1), Some(order(3)));
+}
@@ -0,0 +1,37 @@
+
+use crate::book::{ Book, BookView, Level, LevelTracker, Montage, QuoteTracker };
+use crate::msg::Side;
+use super::helpers::*;
+
+#[test]
+fn views_share_one_book() {
+
+    let mut book = Book::new();
+    let mut quotes = QuoteTracker::new();
+    let mut levels = LevelTracker::new();
+    let mut montage = Montage::new();
+    // The same view with a book of its own.
+    let mut owned = LevelTracker::new().owned();
+
+    for msg in [
+        add_mpid(1, 1, Side::Buy, 100, 10_0000, "ABCD"),
+        add(1, 2, Side::Sell, 200, 10_0100),
+        executed(1, 1, 40),
+        replaced(1, 2, 3, 300, 10_0200),
+    ] {
+        let update = book.update(&msg);
+        quotes.apply(&book, &update, &msg);
+        montage.apply(&book, &update, &msg);
+        let depth = levels.apply(&book, &update, &msg).unwrap();
+        assert_eq!(depth.levels, owned.update(&msg).unwrap().levels);
+    }
+
+    // Every view agrees with the one book.
+    assert_eq!(book.orders().len(), 2);
+    assert_eq!(quotes.quote(1), (
+        Some(Level { price: price(10_0000), size: 60 }),
+        Some(Level { price: price(10_0200), size: 300 }),
+    ));
+    let bid = montage.quote(1, mpid("ABCD")).unwrap().bid;
+    assert_eq!(bid, Some(Level { price: price(10_0000), size: 60 }));
+}
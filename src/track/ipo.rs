@@ -0,0 +1,264 @@
+
+use std::collections::HashMap;
+use nsdq_util::{ NaiveTime, Price, StockSymbol };
+
+use crate::msg::{ 
+    ItchMessage, 
+    CrossType,
+    DirectListingWithCapitalRaise,
+    IpoQuotationReleaseQualifier,
+    TradingActionReason,
+    TradingState,
+};
+
+/// Something that happened to a new issue, stamped with the message time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IssueEvent {
+
+    /// Locate code of the issue.
+    pub stock_locate: u16,
+    /// Time the message was generated.
+    pub timestamp: NaiveTime,
+    /// What happened.
+    pub kind: IssueEventKind,
+}
+
+/// Steps in the lifecycle of a new issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueEventKind {
+
+    /// Security was set up for IPO release in the stock directory.
+    Listed,
+    /// Market Operations entered (or updated) the IPO for release.
+    ReleaseAnticipated { 
+        release_time: NaiveTime, 
+        ipo_price: Price<u32, 4>,
+    },
+    /// Market Operations canceled or postponed the IPO release.
+    ReleaseCanceled,
+    /// Trading action reported the issue as not yet trading.
+    NotYetTrading,
+    /// DLCR price discovery update.
+    DlcrUpdate(DirectListingWithCapitalRaise),
+    /// The IPO positioning window was extended.
+    PositioningWindowExtended,
+    /// The security was released for quotation.
+    Released,
+    /// Trading state changed to the given state.
+    StateChanged(TradingState),
+    /// The opening (halt/IPO) cross printed.
+    OpeningCross { 
+        price: Price<u32, 4>, 
+        quantity: u32, 
+        match_number: u64,
+    },
+}
+
+/// Where a new issue is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueStatus {
+
+    /// Announced, but no release time has been confirmed.
+    Announced,
+    /// Release time set by Market Operations.
+    Anticipated,
+    /// Release canceled or postponed.
+    Canceled,
+    /// Released for quotation, awaiting the opening cross.
+    Released,
+    /// Opening cross has printed.
+    Opened,
+}
+
+/// Everything known about a single new issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewIssue {
+
+    /// Locate code of the issue.
+    pub stock_locate: u16,
+    /// Symbol of the issue, once seen.
+    pub stock: Option<StockSymbol>,
+    /// Current lifecycle status.
+    pub status: IssueStatus,
+    /// Latest anticipated quotation release time.
+    pub release_time: Option<NaiveTime>,
+    /// Latest IPO price.
+    pub ipo_price: Option<Price<u32, 4>>,
+    /// Latest DLCR price discovery message, if this is a DLCR listing.
+    pub dlcr: Option<DirectListingWithCapitalRaise>,
+    /// Number of times the positioning window was extended.
+    pub extensions: u32,
+    /// Result of the opening cross, once printed.
+    pub opening_cross: Option<IssueEvent>,
+    /// Every recorded event, in the order received.
+    pub timeline: Vec<IssueEvent>,
+}
+
+impl NewIssue {
+
+    fn new(stock_locate: u16) -> Self {
+        Self {
+            stock_locate,
+            stock: None,
+            status: IssueStatus::Announced,
+            release_time: None,
+            ipo_price: None,
+            dlcr: None,
+            extensions: 0,
+            opening_cross: None,
+            timeline: Vec::new(),
+        }
+    }
+
+    /// True if the issue is a Direct Listing with Capital Raise.
+    pub fn is_dlcr(&self) -> bool { self.dlcr.is_some() }
+
+    /// Lower and upper price limits for the listing, if known.
+    /// Uses the DLCR limits when available.
+    pub fn price_limits(&self) -> Option<(Price<u32, 4>, Price<u32, 4>)> {
+        self.dlcr.map(|d| (d.min_price, d.max_price))
+    }
+
+    /// Lower and upper auction collars, if known (DLCR only).
+    pub fn collars(&self) -> Option<(Price<u32, 4>, Price<u32, 4>)> {
+        self.dlcr.map(|d| (d.lower_collar, d.upper_collar))
+    }
+
+    /// Time of the first recorded event.
+    pub fn announced_at(&self) -> Option<NaiveTime> {
+        self.timeline.first().map(|e| e.timestamp)
+    }
+
+    /// Time of the first print (the opening cross).
+    pub fn first_print_at(&self) -> Option<NaiveTime> {
+        self.opening_cross.map(|e| e.timestamp)
+    }
+}
+
+/// Follows every new issue (IPO or DLCR) from announcement to first print.
+///
+/// An issue is tracked once it appears in a `QuotingPeriodUpdate`, 
+/// a `DirectListingWithCapitalRaise`, an IPO-related `TradingAction`, 
+/// or a `StockDirectory` flagged for IPO release. 
+/// Its first `CrossType::Halt` cross trade that executes shares 
+/// is recorded as the opening cross.
+#[derive(Debug, Clone, Default)]
+pub struct IpoTracker {
+    issues: HashMap<u16, NewIssue>,
+}
+
+impl IpoTracker {
+
+    /// Create an empty tracker.
+    pub fn new() -> Self { Self::default() }
+
+    /// Apply a message, returning the event recorded for it (if any).
+    pub fn update(&mut self, msg: &ItchMessage) -> Option<IssueEvent> {
+
+        use IssueEventKind::*;
+
+        let meta = msg.metadata();
+        let locate = meta.stock_locate;
+        let tracked = self.issues.contains_key(&locate);
+
+        let (stock, kind) = match msg {
+
+            ItchMessage::StockDirectory { body, .. } => {
+                if body.ipo_flag != Some(true) && !tracked { return None }
+                (Some(body.stock), Listed)
+            },
+
+            ItchMessage::QuotingPeriodUpdate { body, .. } => {
+                match body.qualifier {
+                    IpoQuotationReleaseQualifier::Anticipated => {
+                        (None, ReleaseAnticipated { 
+                            release_time: body.release_time, 
+                            ipo_price: body.ipo_price,
+                        })
+                    },
+                    IpoQuotationReleaseQualifier::Canceled => {
+                        (None, ReleaseCanceled)
+                    },
+                }
+            },
+
+            ItchMessage::DirectListingWithCapitalRaise { body, .. } => {
+                (Some(body.stock), DlcrUpdate(*body))
+            },
+
+            ItchMessage::TradingAction { body, .. } => {
+                match body.reason {
+                    TradingActionReason::IpoIssue => {
+                        (Some(body.stock), NotYetTrading)
+                    },
+                    TradingActionReason::IpoSecurityReleased => {
+                        (Some(body.stock), Released)
+                    },
+                    TradingActionReason::IpoPositioningWindowExtension => {
+                        (Some(body.stock), PositioningWindowExtended)
+                    },
+                    _ if tracked => (Some(body.stock), StateChanged(body.state)),
+                    _ => return None,
+                }
+            },
+
+            ItchMessage::CrossTrade { body, .. } => {
+                // A cross that executed no shares is not a print.
+                if !tracked || body.cross_type != CrossType::Halt || body.quantity == 0 { 
+                    return None 
+                }
+                (Some(body.stock), OpeningCross { 
+                    price: body.price, 
+                    quantity: body.quantity, 
+                    match_number: body.match_number,
+                })
+            },
+
+            _ => return None,
+        };
+
+        let issue = self.issues.entry(locate)
+            .or_insert_with(|| NewIssue::new(locate));
+
+        // Once opened, the issue trades like any other security.
+        if issue.status == IssueStatus::Opened { return None }
+
+        if stock.is_some() { issue.stock = stock }
+
+        let event = IssueEvent { 
+            stock_locate: locate, 
+            timestamp: meta.timestamp(), 
+            kind,
+        };
+
+        match kind {
+            ReleaseAnticipated { release_time, ipo_price } => {
+                issue.release_time = Some(release_time);
+                issue.ipo_price = Some(ipo_price);
+                issue.status = IssueStatus::Anticipated;
+            },
+            ReleaseCanceled => issue.status = IssueStatus::Canceled,
+            DlcrUpdate(dlcr) => issue.dlcr = Some(dlcr),
+            PositioningWindowExtended => issue.extensions += 1,
+            Released => issue.status = IssueStatus::Released,
+            OpeningCross { .. } => {
+                issue.opening_cross = Some(event);
+                issue.status = IssueStatus::Opened;
+            },
+            Listed | NotYetTrading | StateChanged(_) => {},
+        }
+
+        issue.timeline.push(event);
+        Some(event)
+    }
+
+    /// Look up an issue by locate code.
+    pub fn issue(&self, stock_locate: u16) -> Option<&NewIssue> {
+        self.issues.get(&stock_locate)
+    }
+
+    /// Iterate over all tracked issues (in no particular order).
+    pub fn issues(&self) -> impl Iterator<Item = &NewIssue> {
+        self.issues.values()
+    }
+}
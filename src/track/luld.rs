@@ -0,0 +1,353 @@
+
+use std::collections::{ HashMap, VecDeque };
+use nsdq_util::{ NaiveTime, Price };
+
+use crate::book::{ Book, BookView, OrderUpdate };
+use crate::clock::{ SECOND, MINUTE, HOUR, MARKET_CLOSE, MARKET_OPEN };
+use crate::msg::{ 
+    ItchMessage, 
+    CrossType, 
+    LuldTier, 
+    TradingActionReason, 
+    TradingState,
+};
+use crate::session::Phase;
+
+/// Bands are doubled for the first 15 minutes of the day,
+/// and for the last 25 minutes for securities priced at $3.00 or less.
+const OPEN_DOUBLED_UNTIL: u64 = 9 * HOUR + 45 * MINUTE;
+const CLOSE_DOUBLED_FOR: u64 = 25 * MINUTE;
+
+/// The reference price is the mean price over the preceding five minutes,
+/// recalculated every 30 seconds and only replaced if it moved by 1% or more.
+const WINDOW: u64 = 5 * MINUTE;
+const RECALCULATE: u64 = 30 * SECOND;
+
+/// Price thresholds, in units of $0.0001.
+const THREE_DOLLARS: u32 = 3_0000;
+const SEVENTY_FIVE_CENTS: u32 = 7500;
+const FIFTEEN_CENTS: u64 = 1500;
+
+/// Limit Up / Limit Down price bands for a security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bands {
+
+    /// Reference price the bands are calculated from.
+    pub reference: Price<u32, 4>,
+    /// Lowest price at which the security may trade.
+    pub lower: Price<u32, 4>,
+    /// Highest price at which the security may trade.
+    pub upper: Price<u32, 4>,
+}
+
+/// Which band the quote has reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitSide {
+    /// The best bid is at the upper band.
+    Up,
+    /// The best offer is at the lower band.
+    Down,
+}
+
+/// Something that happened to a security's LULD state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LuldEvent {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Time of the message that caused the event.
+    pub timestamp: NaiveTime,
+    /// What happened.
+    pub kind: LuldEventKind,
+}
+
+/// Changes in a security's LULD state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LuldEventKind {
+
+    /// The bands moved (new reference price or time-of-day parameters).
+    BandsChanged(Bands),
+    /// The best bid or offer reached a band: a trading pause may follow.
+    LimitState(LimitSide),
+    /// The quote moved back inside the bands.
+    LimitCleared,
+    /// Nasdaq paused the security for volatility.
+    /// `predicted` is true if a limit state was already flagged.
+    Paused { predicted: bool },
+    /// Auction collars reported for the reopening after a pause.
+    Collar { 
+        reference: Price<u32, 4>, 
+        lower: Price<u32, 4>, 
+        upper: Price<u32, 4>, 
+        extension: u32,
+    },
+}
+
+/// LULD state of a single security.
+#[derive(Debug, Clone)]
+struct Security {
+    tier: LuldTier,
+    /// Leverage applied to the percentage parameters (1 if unleveraged).
+    leverage: u32,
+    reference: Option<u32>,
+    window: VecDeque<(u64, u32)>,
+    sum: u64,
+    calculated_at: u64,
+    bands: Option<Bands>,
+    limit: Option<LimitSide>,
+    paused: bool,
+}
+
+impl Security {
+
+    /// Set a new reference price, discarding the averaging window.
+    fn reset(&mut self, price: u32, nanos: u64) {
+        self.reference = Some(price);
+        self.window.clear();
+        self.sum = 0;
+        self.calculated_at = nanos;
+    }
+
+    fn trade(&mut self, price: u32, nanos: u64) {
+
+        self.window.push_back((nanos, price));
+        self.sum += price as u64;
+        while let Some(&(t, p)) = self.window.front() {
+            if t + WINDOW > nanos { break }
+            self.window.pop_front();
+            self.sum -= p as u64;
+        }
+
+        let Some(reference) = self.reference else {
+            self.reference = Some(price);
+            self.calculated_at = nanos;
+            return
+        };
+        if nanos - self.calculated_at >= RECALCULATE {
+            self.calculated_at = nanos;
+            let mean = (self.sum / self.window.len() as u64) as u32;
+            if mean.abs_diff(reference) as u64 * 100 >= reference as u64 {
+                self.reference = Some(mean);
+            }
+        }
+    }
+
+    fn bands(&self, nanos: u64, close: u64) -> Option<Bands> {
+
+        let reference = self.reference?;
+        let width = band_width(self.tier, self.leverage, reference, nanos, close)?;
+        let cents = |p: u64| ((p + 50) / 100 * 100).min(u32::MAX as u64) as u32;
+
+        Some(Bands {
+            reference: crate::book::price(reference),
+            lower: crate::book::price(cents((reference as u64).saturating_sub(width))),
+            upper: crate::book::price(cents(reference as u64 + width)),
+        })
+    }
+}
+
+/// Distance from the reference price to either band, in units of $0.0001,
+/// at `nanos` on a day when regular market hours end at `close`.
+/// `None` for securities without a LULD tier.
+pub fn band_width(
+    tier: LuldTier, 
+    leverage: u32, 
+    reference: u32, 
+    nanos: u64, 
+    close: u64,
+) -> Option<u64> {
+
+    // Percentage parameters in basis points.
+    let mut bps: u64 = match (tier, reference) {
+        (LuldTier::NotAvailable, _) => return None,
+        (LuldTier::Tier1, r) if r > THREE_DOLLARS => 500,
+        (LuldTier::Tier2, r) if r > THREE_DOLLARS => 1000,
+        (_, r) if r >= SEVENTY_FIVE_CENTS => 2000,
+        _ => 7500,
+    };
+    if tier == LuldTier::Tier1 {
+        bps *= leverage.max(1) as u64;
+    }
+
+    let mut width = reference as u64 * bps / 10_000;
+    if reference < SEVENTY_FIVE_CENTS {
+        width = width.min(FIFTEEN_CENTS);
+    }
+
+    let close_doubled = close.saturating_sub(CLOSE_DOUBLED_FOR)..close;
+    let doubled = (MARKET_OPEN..OPEN_DOUBLED_UNTIL).contains(&nanos)
+        || (reference <= THREE_DOLLARS && close_doubled.contains(&nanos));
+    if doubled {
+        width *= 2;
+    }
+
+    Some(width)
+}
+
+/// Calculates Limit Up / Limit Down bands from the trade stream 
+/// and flags when the quote reaches them, ahead of a volatility pause.
+///
+/// As under the LULD plan, a limit state is quote-based: the best offer 
+/// at the lower band or the best bid at the upper band. Trades only 
+/// feed the reference price. The first reference price of the day is the opening print 
+/// (or the first trade after the open), and a halt cross resets it 
+/// after a pause. Tier and ETP leverage come from `StockDirectory`.
+///
+/// Bands apply during regular market hours, as set by the day's 
+/// `SystemEvent`s (or 9:30 to the close if none have been seen).
+/// On early-close days, set the close with `close_at` so that 
+/// the bands are doubled over the right window.
+#[derive(Debug, Clone)]
+pub struct LuldTracker {
+    securities: HashMap<u16, Security>,
+    phase: Phase,
+    close: u64,
+}
+
+impl Default for LuldTracker {
+
+    fn default() -> Self {
+        Self { securities: HashMap::new(), phase: Phase::NotStarted, close: MARKET_CLOSE }
+    }
+}
+
+impl LuldTracker {
+
+    /// Create an empty tracker for a full trading day.
+    pub fn new() -> Self { Self::default() }
+
+    /// Regular market hours end at `nanos` since midnight 
+    /// (e.g. 13:00 on early-close days).
+    pub fn close_at(mut self, nanos: u64) -> Self {
+        self.close = nanos;
+        self
+    }
+
+    /// Current bands of a security, if a reference price has been set.
+    pub fn bands(&self, stock_locate: u16) -> Option<Bands> {
+        self.securities.get(&stock_locate)?.bands
+    }
+
+    /// Band a security's best bid or offer is currently at, if any.
+    pub fn limit_state(&self, stock_locate: u16) -> Option<LimitSide> {
+        self.securities.get(&stock_locate)?.limit
+    }
+}
+
+impl BookView for LuldTracker {
+
+    type Output = Vec<LuldEvent>;
+
+    /// Returns the LULD events the message caused.
+    fn apply(&mut self, book: &Book, update: &OrderUpdate, msg: &ItchMessage) -> Vec<LuldEvent> {
+
+        let meta = msg.metadata();
+        let locate = meta.stock_locate;
+        let event = |kind| LuldEvent { stock_locate: locate, timestamp: meta.timestamp(), kind };
+
+        let sale = super::last_sale(update, msg);
+
+        if let ItchMessage::SystemEvent { body, .. } = msg {
+            self.phase = Phase::after(*body);
+        }
+
+        if let ItchMessage::StockDirectory { body, .. } = msg {
+            let leverage = match body.etp_flag {
+                Some(true) => body.etp_leverage_factor.max(1),
+                _ => 1,
+            };
+            self.securities.insert(locate, Security {
+                tier: body.luld_tier,
+                leverage,
+                reference: None,
+                window: VecDeque::new(),
+                sum: 0,
+                calculated_at: 0,
+                bands: None,
+                limit: None,
+                paused: false,
+            });
+            return Vec::new()
+        }
+
+        let Some(security) = self.securities.get_mut(&locate) else { return Vec::new() };
+        let mut events = Vec::new();
+
+        let trade = match msg {
+            // Opening and halt crosses set the reference price directly.
+            ItchMessage::CrossTrade { body, .. } => {
+                if let (Some(price), CrossType::Opening | CrossType::Halt) = (sale, body.cross_type) {
+                    security.reset(price.val(), meta.nanos);
+                    security.paused = false;
+                    security.limit = None;
+                }
+                None
+            },
+            ItchMessage::TradingAction { body, .. } => {
+                let volatility = matches!(body.reason, 
+                    TradingActionReason::VolatilityPause 
+                    | TradingActionReason::VolatilityPauseStraddleCondition
+                );
+                if body.state == TradingState::Paused && volatility && !security.paused {
+                    events.push(event(LuldEventKind::Paused { predicted: security.limit.is_some() }));
+                }
+                security.paused = body.state != TradingState::Trading;
+                None
+            },
+            ItchMessage::LuldAuctionCollar { body, .. } => {
+                events.push(event(LuldEventKind::Collar {
+                    reference: body.reference_price,
+                    lower: body.lower_price,
+                    upper: body.upper_price,
+                    extension: body.extension,
+                }));
+                None
+            },
+            _ => sale,
+        };
+
+        // LULD bands apply during regular market hours.
+        let in_hours = match self.phase {
+            Phase::NotStarted => (MARKET_OPEN..self.close).contains(&meta.nanos),
+            phase => phase == Phase::MarketHours,
+        };
+        if let Some(price) = trade.filter(|_| in_hours && !security.paused) {
+            security.trade(price.val(), meta.nanos);
+        }
+
+        // Bands also move with the time of day, so check on every message
+        // that may have changed them.
+        let bands = security.bands(meta.nanos, self.close).filter(|_| in_hours);
+        if bands != security.bands {
+            security.bands = bands;
+            if let Some(bands) = bands {
+                events.push(event(LuldEventKind::BandsChanged(bands)));
+            }
+        }
+
+        // While paused the limit state is kept for the reopening cross to reset.
+        if !security.paused {
+            let quote = book.stock(locate);
+            let limit = security.bands.and_then(|bands| {
+                let bid = quote?.best_bid().map(|l| l.price.val());
+                let ask = quote?.best_ask().map(|l| l.price.val());
+                if bid.is_some_and(|p| p >= bands.upper.val()) {
+                    Some(LimitSide::Up)
+                } else if ask.is_some_and(|p| p <= bands.lower.val()) {
+                    Some(LimitSide::Down)
+                } else {
+                    None
+                }
+            });
+            if limit != security.limit {
+                events.push(event(match limit {
+                    Some(side) => LuldEventKind::LimitState(side),
+                    None => LuldEventKind::LimitCleared,
+                }));
+                security.limit = limit;
+            }
+        }
+
+        events
+    }
+}
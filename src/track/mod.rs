@@ -0,0 +1,38 @@
+
+//! Stateful trackers that follow securities across many messages.
+
+/// Lifecycle of new issues (IPO and DLCR) from announcement to first print.
+pub mod ipo;
+/// Limit Up / Limit Down price bands and limit states.
+pub mod luld;
+/// Reg SHO Rule 201 short sale restrictions.
+pub mod short_sale;
+/// Retail Price Improvement interest over the day.
+pub mod rpi;
+
+pub use ipo::{ IpoTracker, NewIssue, IssueEvent, IssueEventKind, IssueStatus };
+pub use luld::{ Bands, LimitSide, LuldEvent, LuldEventKind, LuldTracker };
+pub use short_sale::{ ShortSaleEvent, ShortSaleEventKind, ShortSaleMonitor, ShortSaleStatus };
+pub use rpi::{ RpiEvent, RpiHistory, RpiInterval, RpiSummary, RpiTracker, RpiUpdate };
+
+use nsdq_util::Price;
+
+use crate::book::OrderUpdate;
+use crate::msg::ItchMessage;
+
+// Price of the trade reported by a message, if any (crosses included).
+// Non-printable executions are skipped, and executions without a price 
+// are priced from the order changes the message caused in the book.
+pub(crate) fn last_sale(update: &OrderUpdate, msg: &ItchMessage) -> Option<Price<u32, 4>> {
+
+    let executed = update.iter().next().map(|c| c.order.price);
+    match msg {
+        ItchMessage::OrderExecuted { .. } => executed,
+        ItchMessage::OrderExecutedWithPrice { body, .. } => 
+            body.printable.then_some(body.price),
+        ItchMessage::MatchTrade { body, .. } => Some(body.price),
+        ItchMessage::CrossTrade { body, .. } => 
+            (body.quantity > 0).then_some(body.price),
+        _ => None,
+    }
+}
@@ -0,0 +1,250 @@
+
+use std::collections::HashMap;
+use std::time::Duration;
+use nsdq_util::{ NaiveTime, StockSymbol };
+
+use crate::msg::{ ItchMessage, InterestFlag, Side, SystemEvent, nanos_of_day };
+
+/// Change in RPI interest for a security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpiEvent {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Time the message was generated.
+    pub timestamp: NaiveTime,
+    /// Interest before the message.
+    pub previous: InterestFlag,
+    /// Interest after the message.
+    pub interest: InterestFlag,
+}
+
+/// What a message changed in the `RpiTracker`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpiUpdate {
+
+    /// Interest changed for a security.
+    Interest(RpiEvent),
+    /// The day ended: summaries of every security with RPI activity, 
+    /// by locate code.
+    Day(Vec<RpiSummary>),
+}
+
+/// Period during which one side of a security had RPI interest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpiInterval {
+
+    /// Side with interest.
+    pub side: Side,
+    /// Time the interest appeared.
+    pub start: NaiveTime,
+    /// Time the interest went away (`None` while it is still present).
+    pub end: Option<NaiveTime>,
+}
+
+/// How long a security had RPI interest over the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpiSummary {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Symbol of the security.
+    pub stock: StockSymbol,
+    /// Time with buy side interest.
+    pub buy: Duration,
+    /// Time with sell side interest.
+    pub sell: Duration,
+    /// Time with interest on both sides at once.
+    pub both: Duration,
+    /// Time with interest on either side.
+    pub either: Duration,
+    /// Number of changes in interest.
+    pub changes: u32,
+}
+
+/// RPI interest timeline of a single security.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpiHistory {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Symbol of the security.
+    pub stock: StockSymbol,
+    /// Current interest.
+    pub interest: InterestFlag,
+    /// Every interval of interest, in the order it started.
+    pub intervals: Vec<RpiInterval>,
+    /// Number of changes in interest.
+    pub changes: u32,
+}
+
+fn sides(interest: InterestFlag) -> (bool, bool) {
+    match interest {
+        InterestFlag::BuyAvailable => (true, false),
+        InterestFlag::SellAvailable => (false, true),
+        InterestFlag::AnyAvailable => (true, true),
+        InterestFlag::NoneAvailable => (false, false),
+    }
+}
+
+impl RpiHistory {
+
+    fn new(stock_locate: u16, stock: StockSymbol) -> Self {
+        Self {
+            stock_locate,
+            stock,
+            interest: InterestFlag::NoneAvailable,
+            intervals: Vec::new(),
+            changes: 0,
+        }
+    }
+
+    fn set(&mut self, interest: InterestFlag, at: NaiveTime) {
+
+        let (buy, sell) = sides(self.interest);
+        let (new_buy, new_sell) = sides(interest);
+
+        for (side, before, after) in [(Side::Buy, buy, new_buy), (Side::Sell, sell, new_sell)] {
+            if !before && after {
+                self.intervals.push(RpiInterval { side, start: at, end: None });
+            } else if before && !after {
+                let open = self.intervals.iter_mut()
+                    .rfind(|i| i.side == side && i.end.is_none());
+                if let Some(interval) = open {
+                    interval.end = Some(at);
+                }
+            }
+        }
+
+        self.interest = interest;
+        self.changes += 1;
+    }
+
+    /// Sides with interest at a given time.
+    pub fn interest_at(&self, time: NaiveTime) -> (bool, bool) {
+        let has = |side| self.intervals.iter().any(|i| {
+            i.side == side && i.start <= time && i.end.is_none_or(|end| time < end)
+        });
+        (has(Side::Buy), has(Side::Sell))
+    }
+
+    /// Summarize the timeline, counting intervals still open as ending `until`.
+    pub fn summary(&self, until: NaiveTime) -> RpiSummary {
+
+        let until = nanos_of_day(until);
+        let span = |i: &RpiInterval| {
+            let start = nanos_of_day(i.start);
+            (start, i.end.map_or(until, nanos_of_day).max(start))
+        };
+
+        let total = |side| self.intervals.iter()
+            .filter(|i| i.side == side)
+            .map(|i| { let (start, end) = span(i); end - start })
+            .sum::<u64>();
+        let buy = total(Side::Buy);
+        let sell = total(Side::Sell);
+
+        // Intervals on one side never overlap and are sorted by start, 
+        // so the overlap between sides is found by merging the two lists.
+        let side = |side| self.intervals.iter()
+            .filter(move |i| i.side == side)
+            .map(span)
+            .peekable();
+        let (mut buys, mut sells) = (side(Side::Buy), side(Side::Sell));
+        let mut both = 0;
+        while let (Some(&(b0, b1)), Some(&(s0, s1))) = (buys.peek(), sells.peek()) {
+            both += b1.min(s1).saturating_sub(b0.max(s0));
+            // The interval ending first can't overlap any later ones.
+            if b1 <= s1 { buys.next(); } else { sells.next(); }
+        }
+
+        RpiSummary {
+            stock_locate: self.stock_locate,
+            stock: self.stock,
+            buy: Duration::from_nanos(buy),
+            sell: Duration::from_nanos(sell),
+            both: Duration::from_nanos(both),
+            either: Duration::from_nanos(buy + sell - both),
+            changes: self.changes,
+        }
+    }
+}
+
+/// Keeps the Retail Price Improvement interest of every security
+/// and the intervals during which each side had interest.
+///
+/// History is kept for a single day. The day's summaries are returned 
+/// on `SystemEvent::EndMessages` (or on the next `BeginMessages` if that 
+/// was missed), and history is cleared when locate codes are reassigned 
+/// on `BeginMessages`.
+#[derive(Debug, Clone, Default)]
+pub struct RpiTracker {
+    securities: HashMap<u16, RpiHistory>,
+    // Time of the latest message, and whether the day was summarized.
+    last: Option<NaiveTime>,
+    reported: bool,
+}
+
+impl RpiTracker {
+
+    /// Create an empty tracker.
+    pub fn new() -> Self { Self::default() }
+
+    /// Apply a message, returning the change in interest it caused,
+    /// or the day's summaries when the day ends.
+    pub fn update(&mut self, msg: &ItchMessage) -> Option<RpiUpdate> {
+
+        let meta = msg.metadata();
+        let last = self.last.replace(meta.timestamp());
+        match msg {
+            ItchMessage::SystemEvent { body: SystemEvent::EndMessages, .. } => {
+                self.reported = true;
+                Some(RpiUpdate::Day(self.summaries(meta.timestamp())))
+            },
+            ItchMessage::SystemEvent { body: SystemEvent::BeginMessages, .. } => {
+                // Summarize a day that ended without `EndMessages`.
+                let day = (!self.reported && !self.securities.is_empty())
+                    .then(|| self.summaries(last.unwrap_or(meta.timestamp())));
+                self.securities.clear();
+                self.reported = false;
+                day.map(RpiUpdate::Day)
+            },
+            ItchMessage::RetailPriceImprovement { body, .. } => {
+                let history = self.securities.entry(meta.stock_locate)
+                    .or_insert_with(|| RpiHistory::new(meta.stock_locate, body.stock));
+                let previous = history.interest;
+                if previous == body.interest_flag { return None }
+
+                history.set(body.interest_flag, meta.timestamp());
+                Some(RpiUpdate::Interest(RpiEvent { 
+                    stock_locate: meta.stock_locate, 
+                    timestamp: meta.timestamp(), 
+                    previous, 
+                    interest: body.interest_flag,
+                }))
+            },
+            _ => None,
+        }
+    }
+
+    /// Current interest of a security.
+    pub fn interest(&self, stock_locate: u16) -> InterestFlag {
+        self.securities.get(&stock_locate)
+            .map_or(InterestFlag::NoneAvailable, |h| h.interest)
+    }
+
+    /// Timeline of a security, if it has ever had RPI interest.
+    pub fn history(&self, stock_locate: u16) -> Option<&RpiHistory> {
+        self.securities.get(&stock_locate)
+    }
+
+    /// Summaries for every security with RPI activity, by locate code.
+    /// Interest still present is counted up to `until` (e.g. the end of the day).
+    pub fn summaries(&self, until: NaiveTime) -> Vec<RpiSummary> {
+        let mut summaries: Vec<_> = self.securities.values()
+            .map(|h| h.summary(until))
+            .collect();
+        summaries.sort_by_key(|s| s.stock_locate);
+        summaries
+    }
+}
@@ -0,0 +1,273 @@
+
+use std::collections::HashMap;
+use nsdq_util::{ NaiveTime, Price, StockSymbol };
+
+use crate::book::{ Book, BookView, OrderUpdate, Symbols };
+use crate::clock::{ MARKET_CLOSE, MARKET_OPEN };
+use crate::msg::{ ItchMessage, CrossType, RegShoAction, SystemEvent };
+use crate::session::Phase;
+
+/// Rule 201 is triggered by a decline of 10% or more from the prior close.
+const TRIGGER_BPS: u64 = 1000;
+/// Default decline at which to start warning.
+const DEFAULT_WARN_BPS: u32 = 800;
+
+/// Something that happened to a security's short sale restriction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortSaleEvent {
+
+    /// Locate code of the security.
+    pub stock_locate: u16,
+    /// Symbol of the security.
+    pub stock: StockSymbol,
+    /// Time of the message that caused the event.
+    pub timestamp: NaiveTime,
+    /// What happened.
+    pub kind: ShortSaleEventKind,
+}
+
+/// Predictions and reconciliations made by the `ShortSaleMonitor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortSaleEventKind {
+
+    /// A trade printed within the warning distance of the trigger price.
+    Approaching { 
+        price: Price<u32, 4>, 
+        trigger: Price<u32, 4>,
+    },
+    /// A trade printed at or below the trigger price: 
+    /// Nasdaq is expected to send `RegShoAction::PriceDrop`.
+    Triggered { 
+        price: Price<u32, 4>, 
+        trigger: Price<u32, 4>,
+    },
+    /// Nasdaq reported the status the monitor expected.
+    Confirmed(RegShoAction),
+    /// Nasdaq reported a different status than the monitor expected.
+    Mismatch { 
+        expected: RegShoAction, 
+        actual: RegShoAction,
+    },
+}
+
+/// Rule 201 status of a single security.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShortSaleStatus {
+
+    /// Closing price of the previous day, used for the trigger price.
+    pub prior_close: Option<Price<u32, 4>>,
+    /// Price at which the restriction is triggered.
+    pub trigger: Option<Price<u32, 4>>,
+    /// Lowest trade price of the day.
+    pub low: Option<Price<u32, 4>>,
+    /// Restriction carried over from a trigger on the previous day.
+    pub carried_over: bool,
+    /// Restriction triggered today (predicted or reported by Nasdaq).
+    pub triggered: bool,
+    /// Latest status reported by Nasdaq today.
+    pub reported: Option<RegShoAction>,
+    closing: Option<Price<u32, 4>>,
+    last: Option<Price<u32, 4>>,
+    warned: bool,
+}
+
+impl ShortSaleStatus {
+
+    /// Status Nasdaq should be reporting, based on what the monitor has seen.
+    pub fn expected(&self) -> RegShoAction {
+        if self.triggered {
+            RegShoAction::PriceDrop
+        } else if self.carried_over {
+            RegShoAction::RemainInEffect
+        } else {
+            RegShoAction::NoPriceTest
+        }
+    }
+
+    /// True if short sales are currently restricted.
+    pub fn is_restricted(&self) -> bool {
+        self.triggered || self.carried_over
+    }
+
+    fn set_prior_close(&mut self, price: Price<u32, 4>) {
+        let trigger = price.val() as u64 * (10_000 - TRIGGER_BPS) / 10_000;
+        self.prior_close = Some(price);
+        self.trigger = Some(crate::book::price(trigger as u32));
+    }
+
+    // Roll over to the next trading day.
+    fn next_day(&mut self) {
+
+        let carried_over = self.triggered;
+        let close = self.closing.or(self.last).or(self.prior_close);
+        *self = Self { carried_over, ..Self::default() };
+        if let Some(close) = close {
+            self.set_prior_close(close);
+        }
+    }
+}
+
+/// Predicts Reg SHO Rule 201 short sale restrictions from the trade stream,
+/// and reconciles the predictions with the `RegShoRestriction` messages
+/// Nasdaq actually sends.
+///
+/// The prior close comes from the closing cross (or the last trade 
+/// in regular market hours) of the previous day, or is configured 
+/// with `prior_close`. Regular hours are bounded by the day's 
+/// `BeginMarketHours` and `EndMarketHours` events, so early closes 
+/// are followed (or 9:30 to 16:00 if no `SystemEvent` has been seen).
+/// State is kept by symbol, so a restriction triggered today is carried 
+/// over to the next day even though locate codes are reassigned.
+/// The monitor rolls over on each `SystemEvent::BeginMessages`, 
+/// or explicitly with `next_day`.
+#[derive(Debug, Clone)]
+pub struct ShortSaleMonitor {
+    symbols: Symbols,
+    // Keyed by encoded symbol, since locate codes change from day to day.
+    securities: HashMap<[u8; 8], ShortSaleStatus>,
+    warn_bps: u32,
+    started: bool,
+    phase: Phase,
+}
+
+impl Default for ShortSaleMonitor {
+
+    fn default() -> Self {
+        Self {
+            symbols: Symbols::default(),
+            securities: HashMap::new(),
+            warn_bps: DEFAULT_WARN_BPS,
+            started: false,
+            phase: Phase::NotStarted,
+        }
+    }
+}
+
+impl ShortSaleMonitor {
+
+    /// Create a monitor that warns once a security has declined 8%.
+    pub fn new() -> Self { Self::default() }
+
+    /// Warn once a security has declined by this many basis points 
+    /// from its prior close.
+    pub fn warn_at(mut self, bps: u32) -> Self {
+        self.warn_bps = bps;
+        self
+    }
+
+    /// Configure the prior closing price of a security.
+    pub fn prior_close(&mut self, stock: StockSymbol, price: Price<u32, 4>) {
+        self.securities.entry(stock.encode()).or_default().set_prior_close(price);
+    }
+
+    /// Status of a security, if anything is known about it.
+    pub fn status(&self, stock: &StockSymbol) -> Option<&ShortSaleStatus> {
+        self.securities.get(&stock.encode())
+    }
+
+    /// Securities currently restricted.
+    pub fn restricted(&self) -> impl Iterator<Item = StockSymbol> + '_ {
+        self.securities.iter()
+            .filter(|(_, status)| status.is_restricted())
+            .map(|(stock, _)| StockSymbol::parse(stock).expect("Eight bytes").1)
+    }
+
+    /// Start a new trading day: today's close becomes the prior close,
+    /// and restrictions triggered today are carried over.
+    pub fn next_day(&mut self) {
+        self.symbols = Symbols::default();
+        self.securities.values_mut().for_each(ShortSaleStatus::next_day);
+    }
+}
+
+impl BookView for ShortSaleMonitor {
+
+    type Output = Vec<ShortSaleEvent>;
+
+    /// Returns the short sale events the message caused.
+    fn apply(&mut self, _book: &Book, update: &OrderUpdate, msg: &ItchMessage) -> Vec<ShortSaleEvent> {
+
+        if let ItchMessage::SystemEvent { body, .. } = msg {
+            if *body == SystemEvent::BeginMessages {
+                if self.started {
+                    self.next_day();
+                }
+                self.started = true;
+            }
+            self.phase = Phase::after(*body);
+        }
+
+        self.symbols.update(msg);
+        let sale = super::last_sale(update, msg);
+
+        let meta = msg.metadata();
+        let stock = match msg {
+            ItchMessage::RegShoRestriction { body, .. } => Some(body.stock),
+            _ => self.symbols.get(meta.stock_locate),
+        };
+        let Some(stock) = stock else { return Vec::new() };
+        let event = |kind| ShortSaleEvent { 
+            stock_locate: meta.stock_locate, 
+            stock, 
+            timestamp: meta.timestamp(), 
+            kind,
+        };
+
+        let mut events = Vec::new();
+        match msg {
+            ItchMessage::RegShoRestriction { body, .. } => {
+                let status = self.securities.entry(stock.encode()).or_default();
+                let expected = status.expected();
+                events.push(event(match body.action {
+                    actual if actual == expected => ShortSaleEventKind::Confirmed(actual),
+                    actual => ShortSaleEventKind::Mismatch { expected, actual },
+                }));
+
+                // Nasdaq is authoritative.
+                status.reported = Some(body.action);
+                match body.action {
+                    RegShoAction::PriceDrop => status.triggered = true,
+                    RegShoAction::RemainInEffect => status.carried_over = true,
+                    RegShoAction::NoPriceTest => {
+                        status.triggered = false;
+                        status.carried_over = false;
+                    },
+                }
+            },
+            ItchMessage::CrossTrade { body, .. } if body.cross_type == CrossType::Closing => {
+                self.securities.entry(stock.encode()).or_default().closing = sale;
+            },
+            _ => {},
+        }
+
+        let Some(price) = sale else { return events };
+        let status = self.securities.entry(stock.encode()).or_default();
+        // Without a closing cross, the prior close is the last regular-hours sale.
+        let regular = match self.phase {
+            Phase::NotStarted => (MARKET_OPEN..MARKET_CLOSE).contains(&meta.nanos),
+            phase => phase == Phase::MarketHours,
+        };
+        if regular {
+            status.last = Some(price);
+        }
+        status.low = Some(status.low.map_or(price, |low| if low.val() <= price.val() { low } else { price }));
+
+        let (Some(prior), Some(trigger)) = (status.prior_close, status.trigger) else { 
+            return events 
+        };
+        if status.triggered {
+            return events
+        }
+
+        let decline = (prior.val() as u64).saturating_sub(price.val() as u64) * 10_000;
+        if price.val() <= trigger.val() {
+            status.triggered = true;
+            events.push(event(ShortSaleEventKind::Triggered { price, trigger }));
+        } else if !status.warned && decline >= self.warn_bps as u64 * prior.val() as u64 {
+            status.warned = true;
+            events.push(event(ShortSaleEventKind::Approaching { price, trigger }));
+        }
+
+        events
+    }
+}